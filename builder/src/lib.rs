@@ -2,13 +2,86 @@ use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
 use syn::{
-    self, parse_macro_input, AngleBracketedGenericArguments, DeriveInput, Expr, GenericArgument,
-    Lit, MetaNameValue, Path, PathArguments, Type, TypePath,
+    self, parse_macro_input, AngleBracketedGenericArguments, Attribute, DeriveInput,
+    GenericArgument, LitStr, Path, PathArguments, Type, TypePath,
 };
 
+/// Options collected from the `#[builder(...)]` attributes of a single field.
+#[derive(Default)]
+struct FieldAttrs {
+    /// `#[builder(each = "arg")]`: name of the one-at-a-time setter for a Vec field.
+    each: Option<Ident>,
+    /// `#[builder(required)]`: an Option field which must still be set explicitly.
+    required: bool,
+}
+
+/// Options collected from the `#[builder(...)]` attributes on the struct itself.
+#[derive(Default)]
+struct StructAttrs {
+    /// `#[builder(default)]`: unset fields fall back to `<Target as Default>::default()`.
+    default: bool,
+}
+
+fn parse_struct_attrs(attrs: &[Attribute]) -> syn::Result<StructAttrs> {
+    let mut struct_attrs = StructAttrs::default();
+    for attr in attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            // #[builder(default)]
+            //           ^^^^^^^ <- (bare word)
+            if meta.path.is_ident("default") {
+                struct_attrs.default = true;
+                return Ok(());
+            }
+            Err(syn::Error::new_spanned(
+                &attr.meta,
+                "expected `builder(default)`",
+            ))
+        })?;
+    }
+    Ok(struct_attrs)
+}
+
+fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
+    let mut field_attrs = FieldAttrs::default();
+    for attr in attrs {
+        // #[builder(each = "arg")]
+        //   ^^^^^^^ <- (ident)
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            // #[builder(each = "arg")]
+            //           ^^^^^^^^^^^^ <- (name = literal)
+            if meta.path.is_ident("each") {
+                let literal: LitStr = meta.value()?.parse()?;
+                field_attrs.each = Some(Ident::new(literal.value().as_str(), literal.span()));
+                return Ok(());
+            }
+            // #[builder(required)]
+            //           ^^^^^^^^ <- (bare word)
+            if meta.path.is_ident("required") {
+                field_attrs.required = true;
+                return Ok(());
+            }
+            Err(syn::Error::new_spanned(
+                &attr.meta,
+                "expected `builder(each = \"...\")`",
+            ))
+        })?;
+    }
+    Ok(field_attrs)
+}
+
 #[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    let struct_attrs = match parse_struct_attrs(&input.attrs) {
+        Ok(struct_attrs) => struct_attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let name = input.ident;
     let command_builder_type = Ident::new(&format!("{}Builder", name), Span::call_site());
 
@@ -32,7 +105,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
         ) = ty
         {
             // the path could be std::option::Option also so taking just the last segment
-            return segments.len() > 0 && segments.last().unwrap().ident.to_string() == "Option";
+            return !segments.is_empty() && segments.last().unwrap().ident == "Option";
         }
         false
     };
@@ -61,17 +134,37 @@ pub fn derive(input: TokenStream) -> TokenStream {
         None
     };
 
-    let builder_fields = fields.iter().map(|f| {
+    let mut fields_attrs = Vec::with_capacity(fields.len());
+    for f in fields.iter() {
+        let field_attrs = match parse_field_attrs(&f.attrs) {
+            Ok(field_attrs) => field_attrs,
+            Err(err) => return err.to_compile_error().into(),
+        };
+        if field_attrs.required && !ty_is_option(&f.ty) {
+            return syn::Error::new_spanned(
+                &f.ty,
+                "`builder(required)` is only meaningful on `Option<...>` fields",
+            )
+            .to_compile_error()
+            .into();
+        }
+        fields_attrs.push(field_attrs);
+    }
+    let fields_with_attrs = || fields.iter().zip(fields_attrs.iter());
+
+    let builder_fields = fields_with_attrs().map(|(f, attrs)| {
         let name = &f.ident;
         let ty = &f.ty;
 
         // assume that fields with this attribute have the type Vec stated in the test 07
-        if f.attrs.len() > 0 {
+        if attrs.each.is_some() {
             let vec_ty = get_angle_bracket_type_arg(ty);
             return quote! { #name: Vec<#vec_ty> };
         }
 
-        if ty_is_option(ty) {
+        // required Option fields get an extra layer so that "never set" can be
+        // told apart from "explicitly set to None"
+        if ty_is_option(ty) && !attrs.required {
             return quote! { #name: #ty };
         }
 
@@ -83,92 +176,123 @@ pub fn derive(input: TokenStream) -> TokenStream {
         quote! { #name: Default::default() }
     });
 
-    let custom_attribute_methods = fields.iter().filter_map(|f| {
+    let custom_attribute_methods = fields_with_attrs().filter_map(|(f, attrs)| {
         let name = &f.ident;
         let ty = &f.ty;
-        let attrs = &f.attrs;
-        if !attrs.is_empty() {
-            for attr in attrs.iter() {
-                // #[builder(each = "arg")]
-                //   ^^^^^^^ <- (ident)
-                if attr.path().is_ident("builder") {
-                    let MetaNameValue { path, value, .. }: syn::MetaNameValue =
-                        attr.parse_args().unwrap();
-                    // #[builder(each = "arg")]
-                    //           ^^^^ <- (it's a path, can also have double colon separated path like xyz::each)
-                    if path.segments.len() > 0 && path.segments.last().unwrap().ident == "each" {
-                        // #[builder(each = "arg")]
-                        //           ^^^^^^^^^^^^ <- (expr)
-                        if let Expr::Lit(expr) = value {
-                            // #[builder(each = "arg")]
-                            //                  ^^^^^ <- (literal)
-                            if let Lit::Str(literal) = expr.lit {
-                                let ident = Ident::new(literal.value().as_str(), Span::call_site());
-                                let type_inside_vec = get_angle_bracket_type_arg(ty).unwrap();
-                                return Some(quote! {
-                                    pub fn #ident(&mut self, #ident: #type_inside_vec) -> &mut Self {
-                                        self.#name.push(#ident);
-                                        self
-                                    }
-                                });
-                            }
-                        }
-                    }
-                }
+        let ident = attrs.each.as_ref()?;
+        let type_inside_vec = get_angle_bracket_type_arg(ty).unwrap();
+        Some(quote! {
+            pub fn #ident(&mut self, #ident: #type_inside_vec) -> &mut Self {
+                self.#name.push(#ident);
+                self
             }
-            unimplemented!()
-        }
-        None
+        })
     });
 
-    let setters = fields.iter().filter_map(|f| {
+    let setters = fields_with_attrs().filter_map(|(f, attrs)| {
         let name = &f.ident;
         let ty = &f.ty;
-        let attrs = &f.attrs;
-        if attrs.is_empty() {
-            Some(if ty_is_option(&ty) {
-                // extract the type inside Option<type>
-                let opt_inner_ty = get_angle_bracket_type_arg(&ty).unwrap();
-                quote! {
-                    pub fn #name(&mut self, #name: #opt_inner_ty) -> &mut Self {
-                        self.#name = Some(#name);
-                        self
-                    }
-                }
-            } else {
-                quote! {
-                    pub fn #name(&mut self, #name: #ty) -> &mut Self {
-                        self.#name = Some(#name);
-                        self
-                    }
+        if attrs.each.is_some() {
+            return None;
+        }
+        Some(if ty_is_option(ty) && !attrs.required {
+            // extract the type inside Option<type>
+            let opt_inner_ty = get_angle_bracket_type_arg(ty).unwrap();
+            quote! {
+                pub fn #name(&mut self, #name: #opt_inner_ty) -> &mut Self {
+                    self.#name = Some(#name);
+                    self
                 }
-            })
+            }
         } else {
-            None
-        }
+            quote! {
+                pub fn #name(&mut self, #name: #ty) -> &mut Self {
+                    self.#name = Some(#name);
+                    self
+                }
+            }
+        })
     });
 
-    let set_fields = fields.iter().filter_map(|f| {
+    // fields which make `build()` fail when they were never set; with a
+    // struct-level default there are none
+    let required_fields: Vec<_> = fields_with_attrs()
+        .filter(|_| !struct_attrs.default)
+        .filter(|(f, attrs)| attrs.each.is_none() && (!ty_is_option(&f.ty) || attrs.required))
+        .map(|(f, _)| &f.ident)
+        .collect();
+
+    let set_fields = fields_with_attrs().map(|(f, attrs)| {
         let name = &f.ident;
 
-        // fields with custom attrs are vec
-        if f.attrs.len() > 0 {
-            return Some(quote! { #name: self.#name.clone() });
+        // fields with `each` are vec; with a struct-level default, one that
+        // nothing was pushed to counts as unset
+        if attrs.each.is_some() {
+            if struct_attrs.default {
+                return quote! {
+                    #name: if self.#name.is_empty() {
+                        default.#name
+                    } else {
+                        self.#name.clone()
+                    }
+                };
+            }
+            return quote! { #name: self.#name.clone() };
         }
 
-        if ty_is_option(&f.ty) {
-            return Some(quote! {
+        if ty_is_option(&f.ty) && !attrs.required {
+            if struct_attrs.default {
+                return quote! {
+                    #name: self.#name.take().or(default.#name)
+                };
+            }
+            return quote! {
                 #name: self.#name.take()
-            });
+            };
         }
 
-        Some(quote! {
-            #name: self.#name.take().ok_or(
-                format!("{0} not set; use method {0} to set the {0}'s value.", stringify!(#name))
-            )?
-        })
+        if struct_attrs.default {
+            return quote! {
+                #name: self.#name.take().unwrap_or(default.#name)
+            };
+        }
+
+        // presence has already been checked by `build()`, nothing is taken
+        // out of the builder before that check passes
+        quote! {
+            #name: self.#name.take().unwrap()
+        }
     });
 
+    let build_body = if struct_attrs.default {
+        quote! {
+            let default = <#name as Default>::default();
+            Ok(#name {
+                #(#set_fields,)*
+            })
+        }
+    } else {
+        quote! {
+            if true #(&& self.#required_fields.is_some())* {
+                return Ok(#name {
+                    #(#set_fields,)*
+                });
+            }
+
+            let mut missing: Vec<&'static str> = Vec::new();
+            #(
+                if self.#required_fields.is_none() {
+                    missing.push(stringify!(#required_fields));
+                }
+            )*
+            Err(format!(
+                "missing fields: {}; use the methods of the same name to set their values.",
+                missing.join(", ")
+            )
+            .into())
+        }
+    };
+
     quote!(
         pub struct #command_builder_type {
             #(#builder_fields,)*
@@ -177,10 +301,15 @@ pub fn derive(input: TokenStream) -> TokenStream {
         impl #command_builder_type {
             #(#setters)*
             #(#custom_attribute_methods)*
+            /// Builds the value, or reports every field that was never set.
+            /// With `#[builder(default)]` on the struct, unset fields are
+            /// taken from its `Default` impl instead and this never fails.
+            ///
+            /// Fields are only taken out of the builder once all of them are
+            /// known to be present, so a failed call leaves the builder exactly
+            /// as it was and can be retried after setting the missing fields.
             pub fn build(&mut self) -> Result<#name, Box<dyn std::error::Error>> {
-                Ok(#name {
-                    #(#set_fields,)*
-                })
+                #build_body
             }
         }
 
@@ -0,0 +1,51 @@
+// Some Option fields should not silently default to None. The caller has to
+// make an explicit choice between Some and None, and forgetting to do so is an
+// error just like forgetting any other field.
+//
+// Look for a field attribute #[builder(required)] on Option fields. The
+// builder stores such a field as Option<Option<T>> so that "never set" can be
+// told apart from "set to None", and the generated setter accepts the whole
+// Option<T> rather than the inner T.
+//
+//     impl CommandBuilder {
+//         pub fn current_dir(&mut self, current_dir: Option<String>) -> &mut Self {
+//             self.current_dir = Some(current_dir);
+//             self
+//         }
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(required)]
+    current_dir: Option<String>,
+    timeout: Option<u64>,
+}
+
+fn main() {
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("current_dir"));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .current_dir(None)
+        .build()
+        .unwrap();
+    assert!(command.current_dir.is_none());
+    assert!(command.timeout.is_none());
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .current_dir(Some("..".to_owned()))
+        .timeout(30)
+        .build()
+        .unwrap();
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.timeout, Some(30));
+}
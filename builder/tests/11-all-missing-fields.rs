@@ -0,0 +1,47 @@
+// When several fields are missing, `build()` should say so all at once rather
+// than making the caller fix them one at a time.
+//
+// The generated code first checks whether every required field has been set
+// and, if so, builds the struct directly. Only when something is missing does
+// it walk the fields again to collect the names of all unset ones into a
+// single error message.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: Vec<String>,
+    #[builder(required)]
+    current_dir: Option<String>,
+    timeout: Option<u64>,
+}
+
+fn main() {
+    let err = Command::builder().build().err().unwrap().to_string();
+    assert!(err.contains("executable"));
+    assert!(err.contains("args"));
+    assert!(err.contains("current_dir"));
+    assert!(!err.contains("env"));
+    assert!(!err.contains("timeout"));
+
+    let err = Command::builder()
+        .args(vec![])
+        .build()
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("executable"));
+    assert!(!err.contains("args"));
+    assert!(err.contains("current_dir"));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .args(vec!["build".to_owned()])
+        .current_dir(None)
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+}
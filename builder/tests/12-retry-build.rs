@@ -0,0 +1,45 @@
+// A failed `build()` must not consume any of the fields that were already set.
+// Otherwise retrying after setting the missing field would fail again, this
+// time complaining about fields the caller did set.
+//
+// Either `build()` succeeds, or the builder is left exactly as it was before
+// the call.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: Vec<String>,
+    #[builder(required)]
+    current_dir: Option<String>,
+    timeout: Option<u64>,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned());
+    builder.env("RUST_LOG=debug".to_owned());
+    builder.timeout(30);
+
+    let err = builder.build().err().unwrap().to_string();
+    assert!(!err.contains("executable"));
+    assert!(err.contains("args"));
+    assert!(err.contains("current_dir"));
+
+    builder.args(vec!["build".to_owned()]);
+    let err = builder.build().err().unwrap().to_string();
+    assert!(!err.contains("executable"));
+    assert!(!err.contains("args"));
+    assert!(err.contains("current_dir"));
+
+    builder.current_dir(None);
+    let command = builder.build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.env, vec!["RUST_LOG=debug"]);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.timeout, Some(30));
+}
@@ -0,0 +1,56 @@
+// Structs which already implement Default can opt into a builder where every
+// field is optional by putting #[builder(default)] on the struct itself.
+//
+// Any field the caller did not set falls back to the corresponding field of
+// `<Command as Default>::default()`, which the generated `build()` computes
+// once. Since nothing can be missing, `build()` never fails. A field with
+// #[builder(each = "...")] counts as unset until something is pushed to it,
+// and the values pushed then replace the default ones.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(default)]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(required)]
+    timeout: Option<u64>,
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Command {
+            executable: "cargo".to_owned(),
+            args: vec!["build".to_owned()],
+            env: vec!["CARGO_TERM_COLOR=always".to_owned()],
+            current_dir: Some(".".to_owned()),
+            timeout: Some(60),
+        }
+    }
+}
+
+fn main() {
+    let command = Command::builder().build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.env, vec!["CARGO_TERM_COLOR=always"]);
+    assert_eq!(command.current_dir.as_deref(), Some("."));
+    assert_eq!(command.timeout, Some(60));
+
+    let command = Command::builder()
+        .executable("rustc".to_owned())
+        .env("RUST_LOG=debug".to_owned())
+        .current_dir("..".to_owned())
+        .timeout(None)
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "rustc");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.env, vec!["RUST_LOG=debug"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.timeout, None);
+}
@@ -8,6 +8,10 @@ fn tests() {
     t.pass("tests/05-method-chaining.rs");
     t.pass("tests/06-optional-field.rs");
     t.pass("tests/07-repeated-field.rs");
-    //t.compile_fail("tests/08-unrecognized-attribute.rs");
+    t.compile_fail("tests/08-unrecognized-attribute.rs");
     //t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-required-option.rs");
+    t.pass("tests/11-all-missing-fields.rs");
+    t.pass("tests/12-retry-build.rs");
+    t.pass("tests/13-struct-default.rs");
 }
@@ -0,0 +1,347 @@
+//! Parsing of the inert `#[debug ...]` attributes.
+
+use crate::format;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    parenthesized, token, Attribute, Expr, ExprLit, Lit, LitInt, LitStr, Meta, Path, Token,
+    WherePredicate,
+};
+
+/// Options from the `#[debug(...)]` attributes on the struct or enum itself.
+#[derive(Default)]
+pub struct Container {
+    /// `#[debug(bound = "T::Value: Debug")]`: replaces all inferred bounds.
+    pub bound: Option<Vec<WherePredicate>>,
+    /// `#[debug(rename = "User")]`: name printed instead of the struct's.
+    pub rename: Option<LitStr>,
+    /// `#[debug(transparent)]`: print a newtype as its only field.
+    pub transparent: bool,
+    /// `#[debug(non_exhaustive)]`: end the output with `..`, for every variant of an enum.
+    pub non_exhaustive: bool,
+    /// `#[debug(max_depth = 2)]`: elide anything nested deeper than this in
+    /// the fields printed with their own `Debug` impl.
+    pub max_depth: Option<usize>,
+    /// `#[debug(with = "fmt_union")]`: a `fn(&Self, &mut Formatter) -> fmt::Result`
+    /// printing the whole value, as unions require.
+    pub with: Option<Path>,
+    /// `#[repr(packed)]`: fields can't be borrowed in place.
+    pub packed: bool,
+}
+
+/// Options from the `#[debug(...)]` attributes on an enum variant.
+#[derive(Default)]
+pub struct Variant {
+    /// `#[debug(rename = "not-found")]`: name printed instead of the variant's.
+    pub rename: Option<LitStr>,
+    /// `#[debug(non_exhaustive)]`: end the output with `..`.
+    pub non_exhaustive: bool,
+}
+
+/// Options from the `#[debug ...]` attributes on a single field.
+#[derive(Default)]
+pub struct Field {
+    /// `#[debug(skip)]`: leave the field out of the output.
+    pub skip: bool,
+    /// `#[debug(skip_if = "Option::is_none")]`: a `fn(&T) -> bool` deciding at
+    /// formatting time whether to leave the field out.
+    pub skip_if: Option<Path>,
+    /// How the field value is printed.
+    pub value: Value,
+    /// `#[debug(alternate = "{:#x}")]`: format string used instead under `{:#?}`.
+    pub alternate: Option<LitStr>,
+    /// `#[debug(bound = "T: Display")]`: replaces the bounds inferred from this field.
+    pub bound: Option<Vec<WherePredicate>>,
+    /// `#[debug(rename = "user-id")]`: name printed instead of the field's.
+    pub rename: Option<LitStr>,
+    /// `#[debug(max_items = 10)]`: print only the first items of a collection.
+    pub max_items: Option<usize>,
+    /// `#[debug(flatten)]`: print the fields of this `CustomDebug` struct in
+    /// place of the field itself.
+    pub flatten: Option<Span>,
+}
+
+/// How a field value is printed.
+#[derive(Default)]
+pub enum Value {
+    /// Through the field type's own `Debug` impl.
+    #[default]
+    Debug,
+    /// `#[debug = "0b{:08b}"]`: format string applied to the field value.
+    Format(LitStr),
+    /// `#[debug(redact)]` or `#[debug(redact = "****")]`: a fixed mask printed
+    /// instead of the value.
+    Redact(LitStr),
+    /// `#[debug(redact_with = "last4")]`: a `fn(&T) -> impl Display` producing
+    /// the masked value.
+    RedactWith(Path),
+    /// `#[debug(with = "hex::dump")]`: a `fn(&T, &mut Formatter) -> fmt::Result`
+    /// used in place of `Debug::fmt`.
+    With(Path),
+}
+
+impl Field {
+    /// Whether the bounds of this field's type can be inferred, that is the
+    /// field is printed through `Debug` and has no explicit bound.
+    pub fn infers_bounds(&self) -> bool {
+        !self.skip && matches!(self.value, Value::Debug) && self.bound.is_none()
+    }
+
+    /// Whether `#[debug(max_depth = N)]` elides what's nested in this field,
+    /// which only applies to values printed with their own `Debug` impl.
+    pub fn limits_depth(&self) -> bool {
+        matches!(self.value, Value::Debug) && self.alternate.is_none()
+    }
+
+    /// Whether printing this field needs the `__DebugWith` adapter.
+    pub fn uses_debug_with(&self) -> bool {
+        matches!(self.value, Value::With(_)) || self.alternate.is_some() || self.max_items.is_some()
+    }
+
+    fn set_value(&mut self, value: Value, span: Span) -> syn::Result<()> {
+        if !matches!(self.value, Value::Debug) {
+            return Err(syn::Error::new(
+                span,
+                "conflicting ways to print this field; use only one of \
+                 `debug = \"...\"`, `with`, `redact` and `redact_with`",
+            ));
+        }
+        self.value = value;
+        Ok(())
+    }
+}
+
+pub fn parse_container(attrs: &[Attribute]) -> syn::Result<Container> {
+    let mut container = Container::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            // #[repr(packed)] or #[repr(C, packed(2))]
+            if meta.path.is_ident("packed") {
+                container.packed = true;
+            }
+            // skip the arguments of packed(N), align(N) and the like
+            if meta.input.peek(token::Paren) {
+                let content;
+                parenthesized!(content in meta.input);
+                content.parse::<TokenStream>()?;
+            }
+            Ok(())
+        })?;
+    }
+    for attr in debug_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            // #[debug(bound = "T::Value: Debug")]
+            if meta.path.is_ident("bound") {
+                container.bound = Some(parse_bound(meta.value()?.parse()?)?);
+                return Ok(());
+            }
+            // #[debug(rename = "User")]
+            if meta.path.is_ident("rename") {
+                container.rename = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            // #[debug(transparent)]
+            if meta.path.is_ident("transparent") {
+                container.transparent = true;
+                return Ok(());
+            }
+            // #[debug(non_exhaustive)]
+            if meta.path.is_ident("non_exhaustive") {
+                container.non_exhaustive = true;
+                return Ok(());
+            }
+            // #[debug(with = "fmt_union")]
+            if meta.path.is_ident("with") {
+                let path: LitStr = meta.value()?.parse()?;
+                container.with = Some(path.parse()?);
+                return Ok(());
+            }
+            // #[debug(max_depth = 2)]
+            if meta.path.is_ident("max_depth") {
+                let max_depth: LitInt = meta.value()?.parse()?;
+                if max_depth.base10_parse::<usize>()? == 0 {
+                    return Err(syn::Error::new_spanned(
+                        max_depth,
+                        "`max_depth` has to be at least 1, the struct's own fields",
+                    ));
+                }
+                container.max_depth = Some(max_depth.base10_parse()?);
+                return Ok(());
+            }
+            Err(unrecognized(&meta))
+        })?;
+    }
+    Ok(container)
+}
+
+pub fn parse_variant(attrs: &[Attribute]) -> syn::Result<Variant> {
+    let mut variant = Variant::default();
+    for attr in debug_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            // #[debug(rename = "not-found")]
+            if meta.path.is_ident("rename") {
+                variant.rename = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            // #[debug(non_exhaustive)]
+            if meta.path.is_ident("non_exhaustive") {
+                variant.non_exhaustive = true;
+                return Ok(());
+            }
+            Err(unrecognized(&meta))
+        })?;
+    }
+    Ok(variant)
+}
+
+pub fn parse_field(attrs: &[Attribute]) -> syn::Result<Field> {
+    let mut field = Field::default();
+    let mut max_items_span = None;
+    for attr in debug_attrs(attrs) {
+        match &attr.meta {
+            // #[debug = "0b{:08b}"]
+            //           ^^^^^^^^^^ <- (string literal)
+            Meta::NameValue(name_value) => {
+                if let Expr::Lit(ExprLit {
+                    lit: Lit::Str(format),
+                    ..
+                }) = &name_value.value
+                {
+                    format::check_single_argument(format)?;
+                    field.set_value(Value::Format(format.clone()), format.span())?;
+                    continue;
+                }
+            }
+            Meta::List(_) => {
+                attr.parse_nested_meta(|meta| {
+                    let span = meta.path.span();
+                    // #[debug(bound = "T: Display")]
+                    if meta.path.is_ident("bound") {
+                        field.bound = Some(parse_bound(meta.value()?.parse()?)?);
+                        return Ok(());
+                    }
+                    // #[debug(rename = "user-id")]
+                    if meta.path.is_ident("rename") {
+                        field.rename = Some(meta.value()?.parse()?);
+                        return Ok(());
+                    }
+                    // #[debug(max_items = 10)]
+                    if meta.path.is_ident("max_items") {
+                        let max_items: LitInt = meta.value()?.parse()?;
+                        field.max_items = Some(max_items.base10_parse()?);
+                        max_items_span = Some(max_items.span());
+                        return Ok(());
+                    }
+                    // #[debug(flatten)]
+                    if meta.path.is_ident("flatten") {
+                        field.flatten = Some(span);
+                        return Ok(());
+                    }
+                    // #[debug(skip)]
+                    if meta.path.is_ident("skip") {
+                        field.skip = true;
+                        return Ok(());
+                    }
+                    // #[debug(skip_if = "Vec::is_empty")]
+                    if meta.path.is_ident("skip_if") {
+                        let path: LitStr = meta.value()?.parse()?;
+                        field.skip_if = Some(path.parse()?);
+                        return Ok(());
+                    }
+                    // #[debug(alternate = "{:#x}")]
+                    if meta.path.is_ident("alternate") {
+                        let alternate = meta.value()?.parse()?;
+                        format::check_single_argument(&alternate)?;
+                        field.alternate = Some(alternate);
+                        return Ok(());
+                    }
+                    // #[debug(with = "hex::dump")]
+                    if meta.path.is_ident("with") {
+                        let path: LitStr = meta.value()?.parse()?;
+                        return field.set_value(Value::With(path.parse()?), span);
+                    }
+                    // #[debug(redact)] or #[debug(redact = "****")]
+                    if meta.path.is_ident("redact") {
+                        let mask = if meta.input.peek(Token![=]) {
+                            meta.value()?.parse()?
+                        } else {
+                            LitStr::new("<redacted>", span)
+                        };
+                        return field.set_value(Value::Redact(mask), span);
+                    }
+                    // #[debug(redact_with = "last4")]
+                    if meta.path.is_ident("redact_with") {
+                        let path: LitStr = meta.value()?.parse()?;
+                        return field.set_value(Value::RedactWith(path.parse()?), span);
+                    }
+                    Err(unrecognized(&meta))
+                })?;
+                continue;
+            }
+            Meta::Path(_) => {}
+        }
+        return Err(syn::Error::new_spanned(
+            &attr.meta,
+            "expected `debug = \"...\"` or `debug(...)`",
+        ));
+    }
+    if let (true, Some(skip_if)) = (field.skip, &field.skip_if) {
+        return Err(syn::Error::new_spanned(
+            skip_if,
+            "`skip_if` has no effect on a field that is always skipped",
+        ));
+    }
+    if let Some(span) = field.flatten {
+        if field.skip
+            || field.rename.is_some()
+            || field.alternate.is_some()
+            || field.max_items.is_some()
+            || !matches!(field.value, Value::Debug)
+        {
+            return Err(syn::Error::new(
+                span,
+                "`flatten` prints the fields of the inner struct as they are; \
+                 it only combines with `skip_if` and `bound`",
+            ));
+        }
+    }
+    if let Some(span) = max_items_span {
+        if field.alternate.is_some() || !matches!(field.value, Value::Debug) {
+            return Err(syn::Error::new(
+                span,
+                "`max_items` only applies to collections printed through their items' `Debug`",
+            ));
+        }
+    }
+    if let Some(alternate) = &field.alternate {
+        if !matches!(field.value, Value::Debug | Value::Format(_)) {
+            return Err(syn::Error::new(
+                alternate.span(),
+                "`alternate` only applies to fields printed through `Debug` or `debug = \"...\"`",
+            ));
+        }
+    }
+    Ok(field)
+}
+
+fn unrecognized(meta: &ParseNestedMeta) -> syn::Error {
+    let path = &meta.path;
+    meta.error(format_args!(
+        "unrecognized debug attribute `{}`",
+        quote!(#path).to_string().replace(' ', "")
+    ))
+}
+
+fn debug_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("debug"))
+}
+
+/// Parses the comma separated where-clause predicates of `bound = "..."`; an
+/// empty string gives no predicates at all.
+fn parse_bound(bound: LitStr) -> syn::Result<Vec<WherePredicate>> {
+    let predicates = bound.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
@@ -0,0 +1,138 @@
+//! Trait bound inference for the generated `Debug` and `Display` impls.
+//!
+//! The field types of every field printed through its own `Debug` impl are
+//! walked, and:
+//!
+//! - a type parameter `T` used anywhere in the type adds a `T: Debug` bound;
+//! - an associated type rooted at a type parameter, written `T::Value` or
+//!   `<T as Trait>::Value`, adds a `T::Value: Debug` bound instead of `T: Debug`;
+//! - anything inside `PhantomData<...>` is ignored, since `PhantomData<T>` is
+//!   `Debug` for every `T`;
+//! - type parameters not reached by any of the above get no bound at all.
+//!
+//! Fields formatted through `#[debug = "..."]` are bounded the same way by the
+//! trait of each placeholder in their format string instead, `Display` for
+//! `{}`, `LowerHex` for `{:x}` and so on, and a `#[debug(alternate = "...")]`
+//! format string adds the traits of its own placeholders. `{:p}` adds none, as
+//! the value is passed by reference and every reference is `Pointer`. Skipped
+//! or redacted fields and fields printed by a `#[debug(with = "...")]`
+//! function contribute no bounds, since their values never go through a
+//! format trait.
+//!
+//! A `#[debug(flatten)]` field is printed through the hidden `DebugFlatten`
+//! trait instead, so when its type uses a type parameter the whole type gets
+//! a `DebugFlatten` bound, `Meta<T>: DebugFlatten` or `T: DebugFlatten`.
+//!
+//! `#[debug(bound = "...")]` on a field replaces that field's contribution
+//! with the given predicates, and on the struct replaces all inferred bounds.
+//! An empty `bound = ""` removes them.
+//!
+//! `CustomDisplay` applies the same rules with `Display` in place of `Debug`,
+//! to the fields its template refers to.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::visit::{self, Visit};
+use syn::{Generics, Type, TypePath, WherePredicate};
+
+/// Collects the `Debug` bounds needed by `types`, without duplicates.
+pub fn infer<'a>(generics: &Generics, types: impl IntoIterator<Item = &'a Type>) -> Vec<Type> {
+    let mut collector = BoundCollector {
+        params: generics.type_params().map(|param| &param.ident).collect(),
+        bounds: Vec::new(),
+    };
+    for ty in types {
+        collector.visit_type(ty);
+    }
+    collector.bounds
+}
+
+/// The bounds needed by `used_types`, field types paired with the name of a
+/// `core::fmt` trait they're formatted through, grouped by trait.
+pub fn format_predicates(
+    generics: &Generics,
+    used_types: &[(&Type, &'static str)],
+) -> Vec<WherePredicate> {
+    let mut format_traits: Vec<&str> = used_types.iter().map(|(_, name)| *name).collect();
+    format_traits.sort_unstable();
+    format_traits.dedup();
+    let mut predicates = Vec::new();
+    for format_trait in format_traits {
+        let bounds = infer(
+            generics,
+            used_types
+                .iter()
+                .filter(|(_, name)| *name == format_trait)
+                .map(|(ty, _)| *ty),
+        );
+        let format_trait = format_ident!("{}", format_trait);
+        predicates.extend(self::predicates(
+            &bounds,
+            quote!(::core::fmt::#format_trait),
+        ));
+    }
+    predicates
+}
+
+/// Turns each bounded type into a `ty: Trait` where-clause predicate.
+pub fn predicates(bounds: &[Type], trait_path: TokenStream) -> Vec<WherePredicate> {
+    bounds
+        .iter()
+        .map(|ty| syn::parse_quote!(#ty: #trait_path))
+        .collect()
+}
+
+struct BoundCollector<'a> {
+    params: Vec<&'a Ident>,
+    bounds: Vec<Type>,
+}
+
+impl BoundCollector<'_> {
+    fn is_param(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Path(TypePath { qself: None, path }) => path
+                .get_ident()
+                .is_some_and(|ident| self.params.contains(&ident)),
+            _ => false,
+        }
+    }
+
+    fn push(&mut self, ty: &TypePath) {
+        let ty = Type::Path(ty.clone());
+        let key = quote!(#ty).to_string();
+        if !self.bounds.iter().any(|b| quote!(#b).to_string() == key) {
+            self.bounds.push(ty);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for BoundCollector<'_> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        // <T as Trait>::Value
+        if let Some(qself) = &ty.qself {
+            if self.is_param(&qself.ty) {
+                self.push(ty);
+                return;
+            }
+        } else if ty.path.leading_colon.is_none() {
+            // T, or T::Value
+            let first = &ty.path.segments[0];
+            if first.arguments.is_empty() && self.params.contains(&&first.ident) {
+                self.push(ty);
+                return;
+            }
+        }
+
+        // PhantomData<T> is Debug whatever T is
+        if ty
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData")
+        {
+            return;
+        }
+
+        visit::visit_type_path(self, ty);
+    }
+}
@@ -0,0 +1,164 @@
+//! The `CustomDisplay` derive, writing a struct or enum variant through a
+//! `#[display("connection {host}:{port}")]` template.
+
+use crate::bound;
+use crate::format::{self, Arg, Piece};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Attribute, Data, DeriveInput, Fields, LitStr};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+
+    let mut arms = Vec::new();
+    let mut used_types = Vec::new();
+    match &input.data {
+        Data::Struct(data) => {
+            let template = template(&input.attrs)?.ok_or_else(|| {
+                syn::Error::new_spanned(
+                    name,
+                    "CustomDisplay needs a `#[display(\"...\")]` template on the struct",
+                )
+            })?;
+            arms.push(arm(quote!(Self), &data.fields, &template, &mut used_types)?);
+        }
+        Data::Enum(data) => {
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let template = match template(&variant.attrs)? {
+                    Some(template) => template,
+                    // unit variants print their name by default
+                    None if variant.fields.is_empty() => {
+                        LitStr::new(&ident.unraw().to_string(), ident.span())
+                    }
+                    None => {
+                        return Err(syn::Error::new_spanned(
+                            ident,
+                            "CustomDisplay needs a `#[display(\"...\")]` template on every \
+                             variant with fields",
+                        ));
+                    }
+                };
+                arms.push(arm(
+                    quote!(Self::#ident),
+                    &variant.fields,
+                    &template,
+                    &mut used_types,
+                )?);
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "CustomDisplay does not support unions",
+            ));
+        }
+    }
+
+    let body = if arms.is_empty() {
+        // an empty enum can't be instantiated
+        quote! { match *self {} }
+    } else {
+        quote! { match self { #(#arms)* } }
+    };
+
+    // each field is bounded by the format traits of the placeholders using it
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(bound::format_predicates(&input.generics, &used_types));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+/// Finds the template of a `#[display("...")]` attribute, if any.
+fn template(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let mut template = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("display")) {
+        // #[display("connection {host}:{port}")]
+        //           ^^^^^^^^^^^^^^^^^^^^^^^^^^ <- (string literal)
+        if template.is_some() {
+            return Err(syn::Error::new_spanned(attr, "duplicate display template"));
+        }
+        template = Some(attr.parse_args::<LitStr>()?);
+    }
+    Ok(template)
+}
+
+/// The `match self` arm writing `fields` through `template`, where each field
+/// the template refers to is passed as a named `__field_N` argument. The types
+/// of those fields are added to `used_types`, along with the name of the
+/// format trait each placeholder uses.
+fn arm<'a>(
+    path: TokenStream2,
+    fields: &'a Fields,
+    template: &LitStr,
+    used_types: &mut Vec<(&'a syn::Type, &'static str)>,
+) -> syn::Result<TokenStream2> {
+    let error = |message: String| syn::Error::new(template.span(), message);
+
+    let mut pieces = format::parse(&template.value()).map_err(error)?;
+    let mut used = Vec::new();
+    for piece in &mut pieces {
+        let Piece::Placeholder(placeholder) = piece else {
+            continue;
+        };
+        let index = match &placeholder.arg {
+            // {host}
+            Arg::Name(name) => fields
+                .iter()
+                .position(|f| f.ident.as_ref().is_some_and(|ident| ident.unraw() == name))
+                .ok_or_else(|| error(format!("no field named `{}`", name)))?,
+            // {0}
+            Arg::Index(index)
+                if *index < fields.len() && fields.iter().all(|f| f.ident.is_none()) =>
+            {
+                *index
+            }
+            Arg::Index(index) => return Err(error(format!("no field `{}`", index))),
+            Arg::Next => {
+                return Err(error(
+                    "refer to fields by name or index, like `{host}` or `{0}`".to_owned(),
+                ));
+            }
+        };
+        placeholder.arg = Arg::Name(format!("__field_{}", index));
+        if !used.contains(&index) {
+            used.push(index);
+        }
+        let ty = &fields.iter().nth(index).unwrap().ty;
+        used_types.push((ty, placeholder.format_trait()));
+    }
+    used.sort_unstable();
+
+    let bindings: Vec<_> = (0..fields.len())
+        .map(|i| format_ident!("__field_{}", i))
+        .collect();
+    let pattern = match fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|f| &f.ident);
+            quote! { #path { #(#idents: #bindings),* } }
+        }
+        Fields::Unnamed(_) => quote! { #path(#(#bindings),*) },
+        Fields::Unit => quote! { #path },
+    };
+
+    let template = LitStr::new(
+        &pieces.iter().map(ToString::to_string).collect::<String>(),
+        template.span(),
+    );
+    let args = used.iter().map(|&i| &bindings[i]);
+
+    Ok(quote! {
+        #pattern => ::core::write!(f, #template, #(#args = #args),*),
+    })
+}
@@ -0,0 +1,245 @@
+//! A small parser for `format!` style strings, enough to tell which
+//! arguments a template refers to, to catch malformed format specs before
+//! they end up in the expanded code, and to write the template back with its
+//! arguments renamed.
+
+use std::fmt::{self, Display};
+use syn::LitStr;
+
+/// A piece of a format string.
+pub enum Piece {
+    /// Literal text, with `{{` and `}}` already unescaped.
+    Literal(String),
+    /// A `{arg:spec}` placeholder.
+    Placeholder(Placeholder),
+}
+
+pub struct Placeholder {
+    pub arg: Arg,
+    /// Everything after the `:`, if there is one.
+    pub spec: Option<String>,
+}
+
+impl Placeholder {
+    /// The `core::fmt` trait the argument is formatted through, going by the
+    /// type at the end of an already validated spec.
+    pub fn format_trait(&self) -> &'static str {
+        let spec = self.spec.as_deref().unwrap_or("");
+        if spec.ends_with('?') {
+            return "Debug";
+        }
+        match spec.chars().last() {
+            Some('x') => "LowerHex",
+            Some('X') => "UpperHex",
+            Some('o') => "Octal",
+            Some('b') => "Binary",
+            Some('e') => "LowerExp",
+            Some('E') => "UpperExp",
+            Some('p') => "Pointer",
+            _ => "Display",
+        }
+    }
+}
+
+/// The format traits of the placeholders in `format`, an already validated
+/// format string, in order.
+pub fn format_traits(format: &LitStr) -> Vec<&'static str> {
+    parse(&format.value())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|piece| match piece {
+            Piece::Placeholder(placeholder) => Some(placeholder.format_trait()),
+            Piece::Literal(_) => None,
+        })
+        .collect()
+}
+
+/// The argument a placeholder refers to.
+#[derive(PartialEq)]
+pub enum Arg {
+    /// `{}`
+    Next,
+    /// `{0}`
+    Index(usize),
+    /// `{name}`
+    Name(String),
+}
+
+/// Splits `template` into literal text and placeholders.
+pub fn parse(template: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("unmatched `}` in format string".to_owned()),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err("unterminated `{` in format string".to_owned()),
+                    }
+                }
+                if !literal.is_empty() {
+                    pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                }
+                pieces.push(Piece::Placeholder(parse_placeholder(&placeholder)?));
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+    Ok(pieces)
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<Placeholder, String> {
+    let (arg, spec) = match placeholder.split_once(':') {
+        Some((arg, spec)) => (arg.trim(), Some(spec.to_owned())),
+        None => (placeholder.trim(), None),
+    };
+    let arg = if arg.is_empty() {
+        Arg::Next
+    } else if let Ok(index) = arg.parse() {
+        Arg::Index(index)
+    } else if is_identifier(arg) {
+        Arg::Name(arg.to_owned())
+    } else {
+        return Err(format!("invalid argument `{}` in format string", arg));
+    };
+    if let Some(spec) = &spec {
+        check_spec(spec)?;
+    }
+    Ok(Placeholder { arg, spec })
+}
+
+/// Checks `[[fill]align][sign]['#']['0'][width]['.' precision][type]`.
+///
+/// Widths and precisions taken from arguments, `{:1$}` or `{:.*}`, are
+/// rejected as the derives only ever pass the arguments named in placeholders.
+fn check_spec(spec: &str) -> Result<(), String> {
+    let chars: Vec<char> = spec.chars().collect();
+    let mut rest = &chars[..];
+    let is_align = |c: &char| matches!(c, '<' | '^' | '>');
+
+    if rest.len() >= 2 && is_align(&rest[1]) {
+        rest = &rest[2..];
+    } else if rest.first().is_some_and(is_align) {
+        rest = &rest[1..];
+    }
+    if let Some('+' | '-') = rest.first() {
+        rest = &rest[1..];
+    }
+    if let Some('#') = rest.first() {
+        rest = &rest[1..];
+    }
+    if let (Some('0'), next) = (rest.first(), rest.get(1)) {
+        if next != Some(&'$') {
+            rest = &rest[1..];
+        }
+    }
+    rest = count(rest)?;
+    if let Some('.') = rest.first() {
+        if let Some('*') = rest.get(1) {
+            return Err("precision `.*` is not supported here".to_owned());
+        }
+        rest = count(&rest[1..])?;
+    }
+
+    let ty: String = rest.iter().collect();
+    match ty.as_str() {
+        "" | "?" | "x?" | "X?" | "x" | "X" | "o" | "b" | "e" | "E" | "p" => Ok(()),
+        _ => Err(format!("unknown format trait `{}`", ty)),
+    }
+}
+
+/// Skips a literal width or precision, rejecting the `name$` and `1$` forms.
+fn count(spec: &[char]) -> Result<&[char], String> {
+    let len = spec
+        .iter()
+        .take_while(|c| c.is_alphanumeric() || **c == '_')
+        .count();
+    if spec.get(len) == Some(&'$') {
+        return Err("width and precision arguments are not supported here".to_owned());
+    }
+    let digits = spec.iter().take_while(|c| c.is_ascii_digit()).count();
+    Ok(&spec[digits..])
+}
+
+/// Checks that `format` is a valid format string taking exactly one
+/// positional argument, the field value, as `#[debug = "..."]` needs.
+pub fn check_single_argument(format: &LitStr) -> syn::Result<()> {
+    let error = |message: String| syn::Error::new(format.span(), message);
+
+    let pieces = parse(&format.value()).map_err(error)?;
+    let mut next = 0;
+    let mut used = false;
+    for piece in &pieces {
+        let Piece::Placeholder(placeholder) = piece else {
+            continue;
+        };
+        let index = match &placeholder.arg {
+            Arg::Next => {
+                next += 1;
+                next - 1
+            }
+            Arg::Index(index) => *index,
+            Arg::Name(name) => {
+                return Err(error(format!(
+                    "named argument `{}` in format string; refer to the field value with `{{}}`",
+                    name
+                )));
+            }
+        };
+        if index != 0 {
+            return Err(error(
+                "format string takes exactly one argument, the field value".to_owned(),
+            ));
+        }
+        used = true;
+    }
+    if !used {
+        return Err(error(
+            "format string never refers to the field value; use `{}` for it".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+}
+
+impl Display for Piece {
+    /// Writes the piece back in format string syntax.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Piece::Literal(literal) => f.write_str(&literal.replace('{', "{{").replace('}', "}}")),
+            Piece::Placeholder(placeholder) => {
+                f.write_str("{")?;
+                match &placeholder.arg {
+                    Arg::Next => {}
+                    Arg::Index(index) => write!(f, "{}", index)?,
+                    Arg::Name(name) => f.write_str(name)?,
+                }
+                if let Some(spec) = &placeholder.spec {
+                    write!(f, ":{}", spec)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
@@ -0,0 +1,702 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
+
+mod attr;
+mod bound;
+mod display;
+mod format;
+mod support;
+
+#[proc_macro_derive(CustomDebug, attributes(debug))]
+pub fn derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    display::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A struct, or one variant of an enum, together with its parsed field attributes.
+struct Variant<'a> {
+    /// `Self` for structs, `Self::Variant` for enum variants.
+    path: TokenStream2,
+    /// The name printed for the struct or variant.
+    name: String,
+    fields: &'a Fields,
+    attrs: Vec<attr::Field>,
+    /// Print the only field in place of the whole struct.
+    transparent: bool,
+    /// End the output with `..`, as some fields aren't shown.
+    non_exhaustive: bool,
+    /// Copy the fields out of a `#[repr(packed)]` struct instead of binding
+    /// references to them.
+    packed: bool,
+}
+
+/// A field as it shows up in the output.
+struct Shown {
+    name: String,
+    value: TokenStream2,
+    /// `#[debug(skip_if = "...")]`: the condition under which it's left out.
+    skip_if: Option<TokenStream2>,
+    /// `#[debug(flatten)]`: the span of the field type, whose own fields are
+    /// shown instead.
+    flatten: Option<Span>,
+}
+
+impl<'a> Variant<'a> {
+    fn new(
+        path: TokenStream2,
+        ident: &Ident,
+        variant: attr::Variant,
+        fields: &'a Fields,
+    ) -> syn::Result<Self> {
+        let attrs: Vec<attr::Field> = fields
+            .iter()
+            .map(|f| attr::parse_field(&f.attrs))
+            .collect::<syn::Result<_>>()?;
+        for (f, attrs) in fields.iter().zip(&attrs) {
+            if let (None, Some(rename)) = (&f.ident, &attrs.rename) {
+                return Err(syn::Error::new_spanned(
+                    rename,
+                    "`rename` only applies to named fields",
+                ));
+            }
+            if let (None, Some(span)) = (&f.ident, attrs.flatten) {
+                return Err(syn::Error::new(
+                    span,
+                    "`flatten` only applies to named fields",
+                ));
+            }
+        }
+        Ok(Variant {
+            path,
+            name: variant
+                .rename
+                .map_or_else(|| ident.unraw().to_string(), |rename| rename.value()),
+            fields,
+            // skipped fields make the output non-exhaustive by themselves
+            non_exhaustive: variant.non_exhaustive || attrs.iter().any(|attrs| attrs.skip),
+            attrs,
+            transparent: false,
+            packed: false,
+        })
+    }
+
+    fn bindings(&self) -> Vec<Ident> {
+        (0..self.fields.len())
+            .map(|i| format_ident!("__field_{}", i))
+            .collect()
+    }
+
+    /// The pattern binding every field of this variant to its `__field_N`.
+    fn pattern(&self, bindings: &[Ident]) -> TokenStream2 {
+        let path = &self.path;
+        match self.fields {
+            // bound by `copies` instead
+            Fields::Named(_) if self.packed => quote! { #path { .. } },
+            Fields::Unnamed(_) if self.packed => quote! { #path(..) },
+            Fields::Named(fields) => {
+                let idents = fields.named.iter().map(|f| &f.ident);
+                quote! { #path { #(#idents: #bindings),* } }
+            }
+            Fields::Unnamed(_) => quote! { #path(#(#bindings),*) },
+            Fields::Unit => quote! { #path },
+        }
+    }
+
+    /// For `#[repr(packed)]` structs, a statement binding the `__field_N` of
+    /// every field that isn't skipped to a reference to its copy returned by
+    /// `__debug_copies`.
+    fn copies(&self, receiver: &TokenStream2, bindings: &[Ident]) -> TokenStream2 {
+        if !self.packed {
+            return quote!();
+        }
+        let bindings = bindings
+            .iter()
+            .zip(&self.attrs)
+            .filter(|(_, attrs)| !attrs.skip)
+            .map(|(binding, _)| binding);
+        quote! { let (#(#bindings,)*) = &#receiver.__debug_copies(); }
+    }
+
+    /// For `#[repr(packed)]` structs, the `__debug_copies` method returning a
+    /// tuple of copies of the fields that aren't skipped. Every impl reads the
+    /// fields through it, so a field type that isn't `Copy` is reported once,
+    /// at the field.
+    fn copies_fn(&self) -> TokenStream2 {
+        let (types, copies): (Vec<_>, Vec<_>) = self
+            .fields
+            .iter()
+            .zip(&self.attrs)
+            .enumerate()
+            .filter(|(_, (_, attrs))| !attrs.skip)
+            .map(|(i, (f, _))| {
+                let span = f.ty.span();
+                let member = match &f.ident {
+                    Some(ident) => quote!(#ident),
+                    None => {
+                        let mut index = syn::Index::from(i);
+                        index.span = span;
+                        index.into_token_stream()
+                    }
+                };
+                let receiver = quote_spanned!(span=> self);
+                (&f.ty, quote_spanned!(span=> __copy(#receiver.#member)))
+            })
+            .unzip();
+        let copy = support::copy();
+        quote! {
+            #[doc(hidden)]
+            fn __debug_copies(&self) -> (#(#types,)*) {
+                #copy
+                (#(#copies,)*)
+            }
+        }
+    }
+
+    /// The printed name and value of every field that isn't skipped, where
+    /// tuple fields are named by their index. `depth` is the `Option<usize>`
+    /// expression limiting how deep values printed with their own `Debug` impl
+    /// go, for `#[debug(max_depth = N)]`.
+    fn shown(&self, bindings: &[Ident], depth: Option<&TokenStream2>) -> Vec<Shown> {
+        self.fields
+            .iter()
+            .zip(bindings)
+            .zip(&self.attrs)
+            .enumerate()
+            .filter(|(_, (_, attrs))| !attrs.skip)
+            .map(|(i, ((f, binding), attrs))| Shown {
+                name: match (&attrs.rename, &f.ident) {
+                    (Some(rename), _) => rename.value(),
+                    (None, Some(ident)) => ident.unraw().to_string(),
+                    (None, None) => i.to_string(),
+                },
+                value: match attrs.flatten {
+                    Some(_) => quote!(#binding),
+                    None => {
+                        let value = field_value(binding, attrs);
+                        match depth {
+                            Some(depth) if attrs.limits_depth() => {
+                                limit_depth(binding, attrs, value, depth)
+                            }
+                            _ => value,
+                        }
+                    }
+                },
+                // #[debug(skip_if = "Option::is_none")]
+                skip_if: attrs
+                    .skip_if
+                    .as_ref()
+                    .map(|path| quote_spanned!(path.span()=> #path(#binding))),
+                flatten: attrs.flatten.map(|_| f.ty.span()),
+            })
+            .collect()
+    }
+
+    /// The `match self` arm formatting this variant the way std's
+    /// `#[derive(Debug)]` would, apart from the customized fields.
+    fn arm(&self, depth: Option<&TokenStream2>) -> TokenStream2 {
+        let bindings = self.bindings();
+        let pattern = self.pattern(&bindings);
+        let copies = self.copies(&quote!(self), &bindings);
+        let body = self.body(&bindings, depth);
+        quote! {
+            #pattern => {
+                #copies
+                #body
+            }
+        }
+    }
+
+    /// The expression formatting this variant from its bound fields.
+    fn body(&self, bindings: &[Ident], depth: Option<&TokenStream2>) -> TokenStream2 {
+        let name = &self.name;
+
+        // #[debug(transparent)]
+        if self.transparent {
+            let value = field_value(&bindings[0], &self.attrs[0]);
+            return match depth {
+                // the field takes the place of the struct, one level up
+                Some(depth) if self.attrs[0].limits_depth() => {
+                    let depth = quote!(#depth.map(|d| d + 1));
+                    let value = limit_depth(&bindings[0], &self.attrs[0], value, &depth);
+                    quote! { ::core::fmt::Debug::fmt(#value, f) }
+                }
+                _ => quote! { ::core::fmt::Debug::fmt(#value, f) },
+            };
+        }
+
+        // #[debug(non_exhaustive)]
+        let finish = if self.non_exhaustive {
+            quote!(finish_non_exhaustive)
+        } else {
+            quote!(finish)
+        };
+        let builder = match self.fields {
+            Fields::Named(_) => quote!(f.debug_struct(#name)),
+            Fields::Unnamed(_) => quote!(f.debug_tuple(#name)),
+            Fields::Unit if self.non_exhaustive => {
+                return quote! { f.debug_struct(#name).finish_non_exhaustive() };
+            }
+            Fields::Unit => return quote! { f.write_str(#name) },
+        };
+
+        let shown = self.shown(bindings, depth);
+        let flattens = shown.iter().any(|shown| shown.flatten.is_some());
+        if !flattens && shown.iter().all(|shown| shown.skip_if.is_none()) {
+            let fields = shown.iter().map(|shown| {
+                let value = &shown.value;
+                match self.fields {
+                    Fields::Named(_) => {
+                        let name = &shown.name;
+                        quote!(.field(#name, #value))
+                    }
+                    _ => quote!(.field(#value)),
+                }
+            });
+            return quote! {
+                #builder
+                    #(#fields)*
+                    .#finish()
+            };
+        }
+
+        // some fields are left out depending on their value, or come from
+        // another struct, so they're added one statement at a time
+        let statements = self.statements(&shown, depth);
+        if !flattens {
+            return quote! {{
+                let __builder = &mut #builder;
+                #statements
+                __builder.#finish()
+            }};
+        }
+        let non_exhaustive = self.non_exhaustive;
+        quote! {{
+            let __builder = &mut #builder;
+            let mut __non_exhaustive = #non_exhaustive;
+            #statements
+            if __non_exhaustive {
+                __builder.finish_non_exhaustive()
+            } else {
+                __builder.finish()
+            }
+        }}
+    }
+
+    /// Statements adding the shown fields to `__builder`, a `&mut DebugStruct`
+    /// or `&mut DebugTuple`. Flattened fields also set `__non_exhaustive` when
+    /// their struct leaves out some of its own fields, and pass on `depth`.
+    fn statements(&self, shown: &[Shown], depth: Option<&TokenStream2>) -> TokenStream2 {
+        let depth = match depth {
+            Some(depth) => depth.clone(),
+            None => quote!(::core::option::Option::None),
+        };
+        let statements = shown.iter().map(|shown| {
+            let value = &shown.value;
+            let statement = match (shown.flatten, self.fields) {
+                (Some(span), _) => {
+                    // spanned at the field type, so a type without the trait
+                    // is reported there
+                    let value = value.clone().into_iter().map(|mut tt| {
+                        tt.set_span(span);
+                        tt
+                    });
+                    quote_spanned! {span=>
+                        __non_exhaustive |= ::derive_debug::__private::DebugFlatten::debug_flatten(
+                            #(#value)*,
+                            __builder,
+                            #depth,
+                        );
+                    }
+                }
+                (None, Fields::Named(_)) => {
+                    let name = &shown.name;
+                    quote! { __builder.field(#name, #value); }
+                }
+                (None, _) => quote! { __builder.field(#value); },
+            };
+            match &shown.skip_if {
+                Some(skip_if) => quote! { if !#skip_if { #statement } },
+                None => statement,
+            }
+        });
+        quote! { #(#statements)* }
+    }
+
+    /// The `match self` arm of `DebugFlatten::debug_flatten`, adding the
+    /// fields of this struct to the builder of the struct it's flattened into.
+    fn flatten_arm(&self) -> TokenStream2 {
+        let bindings = self.bindings();
+        let pattern = self.pattern(&bindings);
+        let copies = self.copies(&quote!(self), &bindings);
+        let depth = quote!(__depth);
+        let shown = self.shown(&bindings, Some(&depth));
+        let statements = self.statements(&shown, Some(&depth));
+        let non_exhaustive = self.non_exhaustive;
+        quote! {
+            #pattern => {
+                #copies
+                let mut __non_exhaustive = #non_exhaustive;
+                #statements
+                __non_exhaustive
+            }
+        }
+    }
+
+    /// The `match self` arm of `debug_fields`, visiting the same names and
+    /// values as the `Debug` output shows.
+    fn visit_arm(&self, depth: Option<&TokenStream2>) -> TokenStream2 {
+        let bindings = self.bindings();
+        let pattern = self.pattern(&bindings);
+        let copies = self.copies(&quote!(self), &bindings);
+        let visits = self.shown(&bindings, depth).into_iter().map(|shown| {
+            let Shown {
+                name,
+                value,
+                skip_if,
+                flatten,
+            } = shown;
+            let visit = match flatten {
+                Some(span) => {
+                    let value = value.into_iter().map(|mut tt| {
+                        tt.set_span(span);
+                        tt
+                    });
+                    quote_spanned! {span=>
+                        ::derive_debug::__private::DebugFlatten::debug_flatten_fields(
+                            #(#value)*,
+                            &mut visit,
+                        );
+                    }
+                }
+                None => quote! { visit(#name, #value); },
+            };
+            match skip_if {
+                Some(skip_if) => quote! { if !#skip_if { #visit } },
+                None => visit,
+            }
+        });
+        quote! {
+            #pattern => {
+                #copies
+                #(#visits)*
+            }
+        }
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let container = attr::parse_container(&input.attrs)?;
+
+    let variants = match &input.data {
+        Data::Struct(data) => {
+            let variant = attr::Variant {
+                rename: container.rename,
+                non_exhaustive: container.non_exhaustive,
+            };
+            let mut variant = Variant::new(quote!(Self), name, variant, &data.fields)?;
+            variant.packed = container.packed;
+            if container.transparent {
+                if variant.fields.len() != 1
+                    || variant.attrs[0].skip
+                    || variant.attrs[0].skip_if.is_some()
+                {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        "`debug(transparent)` needs a struct with exactly one field, which is always shown",
+                    ));
+                }
+                variant.transparent = true;
+            }
+            vec![variant]
+        }
+        Data::Enum(_) if container.rename.is_some() || container.transparent => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "enums only take `rename` on their variants, and no `transparent`",
+            ));
+        }
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|v| {
+                let ident = &v.ident;
+                let mut variant = attr::parse_variant(&v.attrs)?;
+                variant.non_exhaustive |= container.non_exhaustive;
+                Variant::new(quote!(Self::#ident), ident, variant, &v.fields)
+            })
+            .collect::<syn::Result<_>>()?,
+        // printed by the container-level `with` function alone
+        Data::Union(_) if container.with.is_some() => Vec::new(),
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "CustomDebug on a union needs `#[debug(with = \"...\")]`, a \
+                 `fn(&Self, &mut fmt::Formatter) -> fmt::Result` printing it",
+            ));
+        }
+    };
+
+    let uses_debug_with = variants
+        .iter()
+        .flat_map(|v| &v.attrs)
+        .any(attr::Field::uses_debug_with);
+
+    // #[debug(max_depth = 2)]: the struct's own fields are at depth 1, so
+    // their values show one level less
+    let depth = container
+        .max_depth
+        .map(|max_depth| quote!(::core::option::Option::Some(#max_depth - 1)));
+    let body = if let Some(with) = &container.with {
+        // #[debug(with = "fmt_union")]
+        quote! { #with(self, f) }
+    } else if variants.is_empty() {
+        // an empty enum can't be instantiated
+        quote! { match *self {} }
+    } else {
+        let arms = variants.iter().map(|v| v.arm(depth.as_ref()));
+        quote! { match self { #(#arms)* } }
+    };
+    // what printing the fields themselves needs
+    let mut field_helpers = quote!();
+    if uses_debug_with {
+        field_helpers.extend(support::debug_with());
+    }
+    let mut helpers = field_helpers.clone();
+    if depth.is_some() {
+        helpers.extend(support::depth_limit());
+    }
+
+    // explicit field bounds always apply; a struct-level bound replaces only
+    // the inferred ones
+    let fields = || variants.iter().flat_map(|v| v.fields.iter().zip(&v.attrs));
+    let mut predicates: Vec<_> = fields()
+        .filter_map(|(_, attrs)| attrs.bound.clone())
+        .flatten()
+        .collect();
+    match container.bound {
+        Some(bound) => predicates.extend(bound),
+        // the container-level `with` function takes care of all fields
+        None if container.with.is_some() => {}
+        None => {
+            let bounds = bound::infer(
+                &input.generics,
+                fields()
+                    .filter(|(_, attrs)| attrs.infers_bounds() && attrs.flatten.is_none())
+                    .map(|(f, _)| &f.ty),
+            );
+            predicates.extend(bound::predicates(&bounds, quote!(::core::fmt::Debug)));
+            // #[debug = "{:x}"] and #[debug(alternate = "{:#x}")]
+            let mut used_types = Vec::new();
+            for (f, attrs) in fields().filter(|(_, attrs)| !attrs.skip && attrs.bound.is_none()) {
+                let format = match &attrs.value {
+                    attr::Value::Format(format) => Some(format),
+                    _ => None,
+                };
+                for format in format.into_iter().chain(&attrs.alternate) {
+                    used_types.extend(
+                        format::format_traits(format)
+                            .into_iter()
+                            .filter(|&format_trait| format_trait != "Pointer")
+                            .map(|format_trait| (&f.ty, format_trait)),
+                    );
+                }
+            }
+            predicates.extend(bound::format_predicates(&input.generics, &used_types));
+            // #[debug(flatten)]
+            let flattened: Vec<Type> = fields()
+                .filter(|(_, attrs)| attrs.infers_bounds() && attrs.flatten.is_some())
+                .map(|(f, _)| f.ty.clone())
+                .filter(|ty| !bound::infer(&input.generics, [ty]).is_empty())
+                .collect();
+            predicates.extend(bound::predicates(
+                &flattened,
+                quote!(::derive_debug::__private::DebugFlatten),
+            ));
+        }
+    }
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.extend(predicates);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut expanded = quote! {
+        impl #impl_generics ::core::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #helpers
+                #body
+            }
+        }
+    };
+
+    // #[repr(packed)]
+    if let ([variant], true, None) = (variants.as_slice(), container.packed, &container.with) {
+        let copies_fn = variant.copies_fn();
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        expanded.extend(quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                #copies_fn
+            }
+        });
+    }
+
+    // lets other structs #[debug(flatten)] this one, which only works for
+    // structs printed field by field
+    if let [variant] = variants.as_slice() {
+        if matches!(variant.fields, Fields::Named(_))
+            && !variant.transparent
+            && container.with.is_none()
+            && matches!(input.data, Data::Struct(_))
+        {
+            let arm = variant.flatten_arm();
+            // #[debug(max_depth = 2)] of the outer struct wins over this one's
+            let own_depth = match container.max_depth {
+                Some(max_depth) => quote!(::core::option::Option::Some(#max_depth - 1)),
+                None => quote!(::core::option::Option::None),
+            };
+            let depth_limit = support::depth_limit();
+            let flatten_fields = if cfg!(feature = "structured") {
+                quote! {
+                    fn debug_flatten_fields(
+                        &self,
+                        visit: &mut dyn ::core::ops::FnMut(&'static str, &dyn ::core::fmt::Debug),
+                    ) {
+                        self.debug_fields(visit)
+                    }
+                }
+            } else {
+                quote!()
+            };
+            expanded.extend(quote! {
+                #[doc(hidden)]
+                impl #impl_generics ::derive_debug::__private::DebugFlatten
+                    for #name #ty_generics #where_clause
+                {
+                    #[allow(unused_mut, unused_variables)]
+                    fn debug_flatten(
+                        &self,
+                        __builder: &mut ::core::fmt::DebugStruct<'_, '_>,
+                        depth: ::core::option::Option<usize>,
+                    ) -> bool {
+                        #field_helpers
+                        #depth_limit
+                        let __depth = depth.or(#own_depth);
+                        match self { #arm }
+                    }
+
+                    #flatten_fields
+                }
+            });
+        }
+    }
+
+    // structured key/value access to the same fields
+    if cfg!(feature = "structured") && container.with.is_none() {
+        let body = if variants.is_empty() {
+            quote! { match *self {} }
+        } else {
+            let arms = variants.iter().map(|v| v.visit_arm(depth.as_ref()));
+            quote! { match self { #(#arms)* } }
+        };
+        expanded.extend(quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Calls `visit` with the name and value of every field shown
+                /// by the `Debug` impl, after skipping, redacting and renaming.
+                #[allow(unused_mut, unused_variables)]
+                pub fn debug_fields(
+                    &self,
+                    mut visit: impl ::core::ops::FnMut(&'static str, &dyn ::core::fmt::Debug),
+                ) {
+                    #helpers
+                    #body
+                }
+            }
+        });
+    }
+
+    Ok(expanded)
+}
+
+/// `value`, the expression printing a field bound to `binding`, wrapped to show
+/// only `depth` levels of what's nested in it.
+fn limit_depth(
+    binding: &Ident,
+    attrs: &attr::Field,
+    value: TokenStream2,
+    depth: &TokenStream2,
+) -> TokenStream2 {
+    match attrs.max_items {
+        // #[debug(max_items = 10)]
+        Some(max_items) => quote!(&__MaxItems(#binding, #max_items, #depth)),
+        None => quote!(&__DepthLimited(#value, #depth)),
+    }
+}
+
+/// The expression passed to `DebugStruct::field` or `DebugTuple::field` for a
+/// field bound to `binding`.
+fn field_value(binding: &Ident, attrs: &attr::Field) -> TokenStream2 {
+    // #[debug(alternate = "{:#x}")]
+    if let Some(alternate) = &attrs.alternate {
+        let normal = match &attrs.value {
+            attr::Value::Format(format) => quote! { ::core::write!(f, #format, value) },
+            _ => quote! { ::core::fmt::Debug::fmt(value, f) },
+        };
+        return quote! {
+            &__DebugWith(#binding, |value, f| {
+                if f.alternate() {
+                    ::core::write!(f, #alternate, value)
+                } else {
+                    #normal
+                }
+            })
+        };
+    }
+
+    // #[debug(max_items = 10)]
+    if let Some(max_items) = attrs.max_items {
+        return quote! {
+            &__DebugWith(#binding, |value, f| {
+                let mut list = f.debug_list();
+                let mut more = 0usize;
+                for (i, item) in value.into_iter().enumerate() {
+                    if i < #max_items {
+                        list.entry(&item);
+                    } else {
+                        more += 1;
+                    }
+                }
+                if more > 0 {
+                    list.entry(&::core::format_args!("... ({} more)", more));
+                }
+                list.finish()
+            })
+        };
+    }
+
+    match &attrs.value {
+        attr::Value::Debug => quote! { #binding },
+        // #[debug = "0b{:08b}"]
+        attr::Value::Format(format) => quote! { &::core::format_args!(#format, #binding) },
+        // #[debug(with = "hex::dump")]
+        attr::Value::With(path) => quote! { &__DebugWith(#binding, #path) },
+        // #[debug(redact)]
+        attr::Value::Redact(mask) => quote! { &::core::format_args!("{}", #mask) },
+        // #[debug(redact_with = "last4")]
+        attr::Value::RedactWith(path) => {
+            quote! { &::core::format_args!("{}", #path(#binding)) }
+        }
+    }
+}
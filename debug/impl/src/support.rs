@@ -0,0 +1,154 @@
+//! Helper items emitted into the generated `fmt` bodies, only when an
+//! attribute needs them.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+/// Adapter giving a `#[debug(with = "...")]` function, or the closure picking
+/// between a normal and an alternate format, the `Debug` impl that
+/// `DebugStruct::field` and friends expect.
+pub fn debug_with() -> TokenStream2 {
+    quote! {
+        struct __DebugWith<'a, T: ?Sized>(
+            &'a T,
+            fn(&T, &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result,
+        );
+
+        impl<T: ?Sized> ::core::fmt::Debug for __DebugWith<'_, T> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                (self.1)(self.0, f)
+            }
+        }
+    }
+}
+
+/// Copies a field out of a `#[repr(packed)]` struct, which can't be borrowed
+/// in place. Calls are spanned at the field type so a field that isn't `Copy`
+/// is reported there.
+pub fn copy() -> TokenStream2 {
+    quote! {
+        fn __copy<T: ::core::marker::Copy>(value: T) -> T {
+            value
+        }
+    }
+}
+
+/// Wrapper for `#[debug(max_depth = N)]`, passing the `Debug` output of one
+/// field value through to the formatter except for whatever is nested more
+/// than the given number of brackets deep, which becomes `..`. `None` prints
+/// the value as it is.
+///
+/// The struct itself is printed by the generated code, so its own fields are
+/// always shown. Brackets nested in a field value are counted on its text,
+/// skipping over string and char literals, so this works for any type whose
+/// `Debug` output looks like `#[derive(Debug)]`'s; a hand-written impl
+/// printing unbalanced brackets or quotes outside of literals throws it off.
+pub fn depth_limit() -> TokenStream2 {
+    quote! {
+        struct __DepthLimited<'a, T: ?Sized>(&'a T, ::core::option::Option<usize>);
+
+        impl<T: ?Sized + ::core::fmt::Debug> ::core::fmt::Debug for __DepthLimited<'_, T> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let max_depth = match self.1 {
+                    ::core::option::Option::Some(max_depth) => max_depth,
+                    ::core::option::Option::None => return ::core::fmt::Debug::fmt(self.0, f),
+                };
+                let alternate = f.alternate();
+                let mut limit = __DepthLimit {
+                    f,
+                    max_depth,
+                    depth: 0,
+                    quote: ::core::option::Option::None,
+                    escaped: false,
+                };
+                if alternate {
+                    ::core::fmt::Write::write_fmt(&mut limit, ::core::format_args!("{:#?}", self.0))
+                } else {
+                    ::core::fmt::Write::write_fmt(&mut limit, ::core::format_args!("{:?}", self.0))
+                }
+            }
+        }
+
+        // #[debug(max_items = N)] under a depth limit: the list itself takes
+        // one level, and the `... (K more)` marker isn't part of the scan
+        struct __MaxItems<'a, T: ?Sized>(&'a T, usize, ::core::option::Option<usize>);
+
+        impl<'a, T: ?Sized> ::core::fmt::Debug for __MaxItems<'a, T>
+        where
+            &'a T: ::core::iter::IntoIterator,
+            <&'a T as ::core::iter::IntoIterator>::Item: ::core::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                if self.2 == ::core::option::Option::Some(0) {
+                    return f.write_str("[..]");
+                }
+                let depth = self.2.map(|d| d - 1);
+                let mut list = f.debug_list();
+                let mut more = 0usize;
+                for (i, item) in ::core::iter::IntoIterator::into_iter(self.0).enumerate() {
+                    if i < self.1 {
+                        list.entry(&__DepthLimited(&item, depth));
+                    } else {
+                        more += 1;
+                    }
+                }
+                if more > 0 {
+                    list.entry(&::core::format_args!("... ({} more)", more));
+                }
+                list.finish()
+            }
+        }
+
+        struct __DepthLimit<'a, 'b> {
+            f: &'a mut ::core::fmt::Formatter<'b>,
+            max_depth: usize,
+            depth: usize,
+            /// The quote of the string or char literal being written.
+            quote: ::core::option::Option<char>,
+            escaped: bool,
+        }
+
+        impl ::core::fmt::Write for __DepthLimit<'_, '_> {
+            fn write_str(&mut self, s: &str) -> ::core::fmt::Result {
+                for c in s.chars() {
+                    if let ::core::option::Option::Some(quote) = self.quote {
+                        if self.escaped {
+                            self.escaped = false;
+                        } else if c == '\\' {
+                            self.escaped = true;
+                        } else if c == quote {
+                            self.quote = ::core::option::Option::None;
+                        }
+                    } else {
+                        match c {
+                            '"' | '\'' => self.quote = ::core::option::Option::Some(c),
+                            '{' | '[' | '(' => {
+                                self.depth += 1;
+                                if self.depth == self.max_depth + 1 {
+                                    ::core::fmt::Write::write_char(self.f, c)?;
+                                    self.f.write_str(if c == '{' { " .. " } else { ".." })?;
+                                }
+                                if self.depth > self.max_depth {
+                                    continue;
+                                }
+                            }
+                            '}' | ']' | ')' if self.depth > self.max_depth => {
+                                self.depth -= 1;
+                                if self.depth == self.max_depth {
+                                    ::core::fmt::Write::write_char(self.f, c)?;
+                                }
+                                continue;
+                            }
+                            '}' | ']' | ')' => self.depth = self.depth.saturating_sub(1),
+                            _ => {}
+                        }
+                    }
+                    if self.depth <= self.max_depth {
+                        ::core::fmt::Write::write_char(self.f, c)?;
+                    }
+                }
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+}
@@ -1,8 +1,30 @@
-use proc_macro::TokenStream;
+// Crates that have the "proc-macro" crate type can only export procedural
+// macros, while #[debug(flatten)] needs a trait that the code generated for
+// one struct implements and the code generated for another one calls. The
+// derives live in the derive_debug-impl crate and are re-exported from here,
+// next to that trait, the same way as for bitfield.
 
-#[proc_macro_derive(CustomDebug)]
-pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+#![no_std]
 
-    unimplemented!()
+pub use derive_debug_impl::{CustomDebug, CustomDisplay};
+
+// Not public API, only used by the code CustomDebug generates.
+#[doc(hidden)]
+pub mod __private {
+    use core::fmt::DebugStruct;
+
+    /// Implemented by `CustomDebug` for every struct with named fields, so
+    /// that other structs can `#[debug(flatten)]` it.
+    pub trait DebugFlatten {
+        /// Adds the fields this struct shows to the builder of the struct it
+        /// is flattened into, returning whether some of them were left out.
+        /// `depth` is how many levels deep their values are printed, from the
+        /// `#[debug(max_depth)]` of that struct, if any.
+        fn debug_flatten(&self, builder: &mut DebugStruct<'_, '_>, depth: Option<usize>) -> bool;
+
+        /// Calls `visit` for the fields this struct shows, for the
+        /// `debug_fields` method of the struct it is flattened into.
+        #[cfg(feature = "structured")]
+        fn debug_flatten_fields(&self, visit: &mut dyn FnMut(&'static str, &dyn core::fmt::Debug));
+    }
 }
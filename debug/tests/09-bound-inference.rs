@@ -0,0 +1,73 @@
+// The full set of rules used to infer the bounds of the generated Debug impl:
+//
+//   - a type parameter used anywhere in a field type gets a `T: Debug` bound;
+//   - an associated type of a type parameter, written either `T::Value` or
+//     `<T as Trait>::Value`, gets a `T::Value: Debug` bound instead;
+//   - type parameters only appearing inside PhantomData get no bound;
+//   - fields with a #[debug = "..."] format string are bounded by the trait
+//     of each placeholder instead, `T: LowerHex` for `{:x}`, except that
+//     `{:p}` needs no bound, as the value is passed by reference.
+//
+// None of the type parameters below implement Debug beyond what these rules
+// require, so any extra bound would make the assertions fail to compile.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Field<A, B: Trait, C: Trait, D, E: 'static> {
+    nested: Vec<Option<A>>,
+    assoc: Option<B::Value>,
+    qualified: Box<<C as Trait>::Value>,
+    marker: PhantomData<(D, fn(D) -> D)>,
+    #[debug = "{:p}"]
+    pointer: &'static E,
+}
+
+#[derive(CustomDebug)]
+pub struct Hex<T, U> {
+    #[debug = "{:#x}"]
+    value: T,
+    #[debug(alternate = "{:e}")]
+    scale: U,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Only the associated types implement Debug.
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    struct NotDebug;
+
+    assert_debug::<Field<u8, Id, Id, NotDebug, NotDebug>>();
+
+    static VALUE: NotDebug = NotDebug;
+    let field: Field<u8, Id, Id, NotDebug, NotDebug> = Field {
+        nested: vec![Some(1), None],
+        assoc: Some(2),
+        qualified: Box::new(3),
+        marker: PhantomData,
+        pointer: &VALUE,
+    };
+    let debug = format!("{:?}", field);
+    assert!(debug.starts_with(
+        "Field { nested: [Some(1), None], assoc: Some(2), qualified: 3, marker: PhantomData"
+    ));
+
+    let hex = Hex {
+        value: 255u8,
+        scale: 1500.0f64,
+    };
+    assert_eq!(format!("{:?}", hex), "Hex { value: 0xff, scale: 1500.0 }");
+    assert!(format!("{:#?}", hex).contains("scale: 1.5e3,"));
+}
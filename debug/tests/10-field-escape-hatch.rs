@@ -0,0 +1,67 @@
+// The escape hatch is also accepted on individual fields, where it replaces
+// only the bounds that would have been inferred from that one field. This is
+// how fields with a #[debug = "..."] format string, which contribute no
+// inferred bounds, can ask for the trait their format needs.
+//
+// An empty `bound = ""` removes bounds instead: on a field it drops the
+// field's contribution and on the struct it drops every inferred bound, which
+// is useful when the type parameters are only used behind a reference to a
+// type with its own unconditional Debug impl.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug, Display};
+use std::marker::PhantomData;
+
+pub struct Opaque<T>(PhantomData<T>);
+
+impl<T> Debug for Opaque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Opaque")
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Labeled<L, T> {
+    #[debug = "<{}>"]
+    #[debug(bound = "L: Display")]
+    label: L,
+    value: T,
+}
+
+#[derive(CustomDebug)]
+pub struct FieldEmpty<T, U> {
+    #[debug(bound = "")]
+    opaque: Opaque<T>,
+    value: U,
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "")]
+pub struct StructEmpty<T> {
+    opaque: Opaque<T>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Labeled<&str, u8>>();
+    assert_debug::<FieldEmpty<NotDebug, u8>>();
+    assert_debug::<StructEmpty<NotDebug>>();
+
+    let labeled = Labeled {
+        label: "id",
+        value: 7,
+    };
+    assert_eq!(format!("{:?}", labeled), "Labeled { label: <id>, value: 7 }");
+
+    let empty: FieldEmpty<NotDebug, u8> = FieldEmpty {
+        opaque: Opaque(PhantomData),
+        value: 1,
+    };
+    assert_eq!(
+        format!("{:?}", empty),
+        "FieldEmpty { opaque: Opaque, value: 1 }"
+    );
+}
@@ -0,0 +1,60 @@
+// Enums are formatted one variant at a time, the same way std's
+// #[derive(Debug)] does it: variants with named fields go through
+// `Formatter::debug_struct`, tuple variants through `Formatter::debug_tuple`
+// and unit variants just print their name.
+//
+// The #[debug = "..."] format strings work on variant fields too, named or
+// not, and bound inference looks at the fields of every variant.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub enum Error<E> {
+    Io {
+        path: &'static str,
+        code: u32,
+    },
+    Parse(usize, &'static str),
+    Code {
+        #[debug = "0x{:02x}"]
+        code: u8,
+    },
+    Inner(E),
+    Eof,
+}
+
+#[derive(Debug)]
+pub enum Reference<E> {
+    Io { path: &'static str, code: u32 },
+    Parse(usize, &'static str),
+    Inner(E),
+    Eof,
+}
+
+#[derive(CustomDebug)]
+pub enum Void {}
+
+fn check(custom: Error<Option<u8>>, std: Reference<Option<u8>>) {
+    assert_eq!(format!("{:?}", custom), format!("{:?}", std));
+    assert_eq!(format!("{:#?}", custom), format!("{:#?}", std));
+}
+
+fn main() {
+    check(
+        Error::Io {
+            path: "/tmp",
+            code: 255,
+        },
+        Reference::Io {
+            path: "/tmp",
+            code: 255,
+        },
+    );
+    check(Error::Parse(3, "x"), Reference::Parse(3, "x"));
+    assert_eq!(format!("{:?}", Error::<u8>::Code { code: 10 }), "Code { code: 0x0a }");
+    check(Error::Inner(Some(1)), Reference::Inner(Some(1)));
+    check(Error::Eof, Reference::Eof);
+
+    fn assert_debug<F: std::fmt::Debug>() {}
+    assert_debug::<Void>();
+}
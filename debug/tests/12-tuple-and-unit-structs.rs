@@ -0,0 +1,32 @@
+// Tuple structs and unit structs print exactly like they would with std's
+// #[derive(Debug)]: `Name(field, ...)` through `Formatter::debug_tuple`, and
+// just `Name` respectively. Fields of a tuple struct accept #[debug = "..."]
+// like named fields do.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Meters(f64);
+
+#[derive(CustomDebug)]
+pub struct Pair<T>(T, #[debug = "0b{:04b}"] u8);
+
+#[derive(CustomDebug)]
+pub struct Marker;
+
+#[derive(CustomDebug)]
+pub struct Empty {}
+
+#[derive(Debug)]
+pub struct StdMeters(f64);
+
+fn main() {
+    assert_eq!(format!("{:?}", Meters(1.5)), "Meters(1.5)");
+    assert_eq!(
+        format!("{:#?}", Meters(1.5)),
+        format!("{:#?}", StdMeters(1.5)).replace("StdMeters", "Meters"),
+    );
+    assert_eq!(format!("{:?}", Pair("a", 5)), r#"Pair("a", 0b0101)"#);
+    assert_eq!(format!("{:?}", Marker), "Marker");
+    assert_eq!(format!("{:?}", Empty {}), "Empty");
+}
@@ -0,0 +1,72 @@
+// Structs holding secrets can still derive CustomDebug without leaking them.
+//
+//   - #[debug(skip)] leaves the field out of the output, which then ends with
+//     `..` to show that something was left out.
+//   - #[debug(redact)] prints `<redacted>` in place of the value, and
+//     #[debug(redact = "****")] prints the given mask instead. Either way the
+//     output doesn't depend on the value, not even on its length.
+//   - #[debug(redact_with = "path::to::fn")] calls a `fn(&T) -> impl Display`
+//     and prints its result, for partial masking such as showing only the last
+//     four characters of a card number.
+//
+// None of these fields are printed through Debug, so they don't need their
+// type to implement it.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+mod mask {
+    pub fn last4(value: &String) -> String {
+        let visible = value.len().saturating_sub(4);
+        format!("{}{}", "*".repeat(visible), &value[visible..])
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Credentials<T> {
+    user: &'static str,
+    #[debug(redact)]
+    password: String,
+    #[debug(redact = "****")]
+    token: T,
+    #[debug(redact_with = "mask::last4")]
+    card: String,
+    #[debug(skip)]
+    session: T,
+}
+
+#[derive(CustomDebug)]
+pub enum Secret {
+    Key(#[debug(redact)] Vec<u8>),
+    Pin {
+        #[debug(skip)]
+        pin: u16,
+        attempts: u8,
+    },
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Credentials<NotDebug>>();
+
+    let credentials = Credentials {
+        user: "root",
+        password: "hunter2".to_owned(),
+        token: 42,
+        card: "4111111111111111".to_owned(),
+        session: 7,
+    };
+    assert_eq!(
+        format!("{:?}", credentials),
+        r#"Credentials { user: "root", password: <redacted>, token: ****, card: ************1111, .. }"#,
+    );
+
+    assert_eq!(format!("{:?}", Secret::Key(vec![1, 2])), "Key(<redacted>)");
+    assert_eq!(
+        format!("{:?}", Secret::Pin { pin: 1234, attempts: 2 }),
+        "Pin { attempts: 2, .. }",
+    );
+}
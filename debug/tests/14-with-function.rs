@@ -0,0 +1,66 @@
+// Some values can't be printed nicely with a format string, such as a byte
+// buffer that should be hex-dumped or a Duration that should be shown in
+// milliseconds.
+//
+// #[debug(with = "path::to::fn")] names a function with the same shape as
+// Debug::fmt,
+//
+//     fn(&T, &mut fmt::Formatter) -> fmt::Result
+//
+// which the generated impl wraps in a small adapter implementing Debug, so it
+// plugs into `DebugStruct::field` like any other field value. Generic
+// functions work as long as their type parameters can be inferred from the
+// field type.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+use std::time::Duration;
+
+mod fmt_with {
+    use std::fmt;
+    use std::time::Duration;
+
+    pub fn hex<B: AsRef<[u8]>>(bytes: &B, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in bytes.as_ref() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+
+    pub fn millis(duration: &Duration, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}ms", duration.as_millis())
+    }
+}
+
+fn len<T>(values: &Vec<T>, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "<{} items>", values.len())
+}
+
+#[derive(CustomDebug)]
+pub struct Packet<T> {
+    #[debug(with = "fmt_with::hex")]
+    payload: [u8; 4],
+    #[debug(with = "fmt_with::millis")]
+    latency: Duration,
+    #[debug(with = "len")]
+    items: Vec<T>,
+}
+
+#[derive(CustomDebug)]
+pub struct Frame(#[debug(with = "fmt_with::hex")] Vec<u8>);
+
+fn main() {
+    struct NotDebug;
+
+    let packet = Packet {
+        payload: [0xde, 0xad, 0xbe, 0xef],
+        latency: Duration::from_micros(1500),
+        items: vec![NotDebug, NotDebug],
+    };
+    assert_eq!(
+        format!("{:?}", packet),
+        "Packet { payload: deadbeef, latency: 1ms, items: <2 items> }",
+    );
+
+    assert_eq!(format!("{:?}", Frame(vec![1, 2, 255])), "Frame(0102ff)");
+}
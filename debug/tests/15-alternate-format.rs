@@ -0,0 +1,62 @@
+// A #[debug = "..."] format string produces its output through format_args!,
+// which knows nothing about the `{:#?}` alternate flag of the outer
+// formatter. A field can name a separate format string to use when the
+// struct is pretty-printed with #[debug(alternate = "...")]; the normal format
+// is then either the #[debug = "..."] one, or the field's own Debug impl.
+//
+// Fields without any format keep going through their own Debug impl with the
+// outer formatter, so nested values are pretty-printed and indented as usual.
+
+use derive_debug::CustomDebug;
+
+#[derive(Debug)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(CustomDebug)]
+pub struct Shape {
+    #[debug = "{:x}"]
+    #[debug(alternate = "{:#010x}")]
+    id: u32,
+    #[debug(alternate = "{:#?}")]
+    origin: Point,
+    #[debug = "{:?}"]
+    #[debug(alternate = "{:#?}")]
+    extent: Point,
+    center: Point,
+}
+
+fn main() {
+    let shape = Shape {
+        id: 255,
+        origin: Point { x: 0, y: 0 },
+        extent: Point { x: 2, y: 3 },
+        center: Point { x: 1, y: 1 },
+    };
+
+    assert_eq!(
+        format!("{:?}", shape),
+        "Shape { id: ff, origin: Point { x: 0, y: 0 }, \
+         extent: Point { x: 2, y: 3 }, center: Point { x: 1, y: 1 } }",
+    );
+
+    let expected = "\
+Shape {
+    id: 0x000000ff,
+    origin: Point {
+        x: 0,
+        y: 0,
+    },
+    extent: Point {
+        x: 2,
+        y: 3,
+    },
+    center: Point {
+        x: 1,
+        y: 1,
+    },
+}";
+    assert_eq!(format!("{:#?}", shape), expected);
+}
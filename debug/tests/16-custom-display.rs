@@ -0,0 +1,102 @@
+// The same crate provides a CustomDisplay derive, so that the Display impl
+// sitting next to every CustomDebug doesn't have to be written by hand.
+//
+// Structs take a #[display("...")] template on the struct itself and enums
+// take one on each variant, where unit variants without a template print
+// their name. Templates interpolate fields by name, `{host}`, or by index for
+// tuple structs and variants, `{0}`, with the usual format specs such as
+// `{port:>5}` applying to the field's Display impl.
+//
+// Bounds are inferred with the same rules as CustomDebug, only for the fields
+// the template actually refers to, and asking for the format trait of each
+// placeholder: Display for `{v}`, Debug for `{v:?}`, LowerHex for `{v:x}`
+// and so on.
+
+use derive_debug::CustomDisplay;
+use std::fmt::Display;
+
+#[derive(CustomDisplay)]
+#[display("connection {host}:{port}")]
+pub struct Connection<H> {
+    host: H,
+    port: u16,
+    retries: Vec<u8>,
+}
+
+#[derive(CustomDisplay)]
+#[display("{0}/{1:03}")]
+pub struct Version(u8, u8);
+
+#[derive(CustomDisplay)]
+pub enum Error<E> {
+    #[display("cannot open {path}: {source}")]
+    Open { path: &'static str, source: E },
+    #[display("bad byte {0:#04x} at {1}")]
+    BadByte(u8, usize),
+    #[display("{{closed}}")]
+    Closed,
+    Eof,
+}
+
+#[derive(CustomDisplay)]
+#[display("{name}")]
+pub struct Tagged<T> {
+    name: &'static str,
+    tag: T,
+}
+
+#[derive(CustomDisplay)]
+#[display("{value:?} ({value}, {bits:#x})")]
+pub struct Reading<T, B> {
+    value: T,
+    bits: B,
+}
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    struct NotDisplay;
+
+    assert_display::<Connection<&str>>();
+    assert_display::<Error<String>>();
+    assert_display::<Tagged<NotDisplay>>();
+    assert_display::<Reading<f32, u8>>();
+
+    let connection = Connection {
+        host: "localhost",
+        port: 8080,
+        retries: vec![],
+    };
+    assert_eq!(connection.to_string(), "connection localhost:8080");
+    assert_eq!(Version(1, 2).to_string(), "1/002");
+
+    let error: Error<&str> = Error::Open {
+        path: "/etc/hosts",
+        source: "denied",
+    };
+    assert_eq!(error.to_string(), "cannot open /etc/hosts: denied");
+    assert_eq!(Error::<&str>::BadByte(255, 3).to_string(), "bad byte 0xff at 3");
+    assert_eq!(Error::<&str>::Closed.to_string(), "{closed}");
+    assert_eq!(Error::<&str>::Eof.to_string(), "Eof");
+
+    let tagged = Tagged {
+        name: "a",
+        tag: NotDisplay,
+    };
+    assert_eq!(tagged.to_string(), "a");
+
+    let reading = Reading {
+        value: "1.5",
+        bits: 255u8,
+    };
+    assert_eq!(reading.to_string(), r#""1.5" (1.5, 0xff)"#);
+
+    #[derive(Debug)]
+    struct OnlyDebug;
+
+    #[derive(CustomDisplay)]
+    #[display("{0:?}")]
+    pub struct Wrapper<T>(T);
+
+    assert_eq!(Wrapper(OnlyDebug).to_string(), "OnlyDebug");
+}
@@ -0,0 +1,14 @@
+// Templates are checked against the fields when the derive runs, so a typo in
+// a field name is reported on the template rather than somewhere inside the
+// expanded write! call.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+#[display("connection {hots}:{port}")]
+pub struct Connection {
+    host: String,
+    port: u16,
+}
+
+fn main() {}
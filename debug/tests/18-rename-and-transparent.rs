@@ -0,0 +1,73 @@
+// Debug output sometimes has to use external names, for example the field
+// names of a wire protocol, rather than Rust identifiers.
+//
+// #[debug(rename = "...")] on a named field changes the name printed for it,
+// on a struct the type name, and on an enum variant the variant name.
+//
+// #[debug(transparent)] on a struct with a single field prints just that
+// field, the way the newtype's inner value would print by itself. Any
+// attributes on the field, such as a format string, still apply.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(rename = "user")]
+pub struct User {
+    #[debug(rename = "user-id")]
+    id: u64,
+    #[debug(rename = "display-name")]
+    name: &'static str,
+    admin: bool,
+}
+
+#[derive(CustomDebug)]
+pub enum Status {
+    #[debug(rename = "not-found")]
+    NotFound,
+    #[debug(rename = "moved")]
+    Moved {
+        #[debug(rename = "location")]
+        to: &'static str,
+    },
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct UserId(u64);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Mask {
+    #[debug = "0b{:04b}"]
+    bits: u8,
+}
+
+#[derive(Debug)]
+pub struct Session {
+    user: UserId,
+    mask: Mask,
+}
+
+fn main() {
+    let user = User {
+        id: 7,
+        name: "root",
+        admin: true,
+    };
+    assert_eq!(
+        format!("{:?}", user),
+        r#"user { user-id: 7, display-name: "root", admin: true }"#,
+    );
+
+    assert_eq!(format!("{:?}", Status::NotFound), "not-found");
+    assert_eq!(
+        format!("{:?}", Status::Moved { to: "/home" }),
+        r#"moved { location: "/home" }"#,
+    );
+
+    let session = Session {
+        user: UserId(7),
+        mask: Mask { bits: 5 },
+    };
+    assert_eq!(format!("{:?}", session), "Session { user: 7, mask: 0b0101 }");
+}
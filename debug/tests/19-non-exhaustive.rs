@@ -0,0 +1,69 @@
+// When some fields aren't shown, the output should make that visible. With
+// #[debug(non_exhaustive)] the generated impl finishes through
+// `DebugStruct::finish_non_exhaustive`, or its `DebugTuple` counterpart,
+// which ends the output with `..` the way std does for types with private
+// fields.
+//
+// This is enabled automatically for any struct or variant with a
+// #[debug(skip)] field. It can also be put on a struct, an enum to apply to
+// all of its variants, or on individual variants.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(non_exhaustive)]
+pub struct Handle {
+    id: u32,
+}
+
+#[derive(CustomDebug)]
+pub struct Cache {
+    len: usize,
+    #[debug(skip)]
+    entries: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+pub struct Pair(u8, #[debug(skip)] u8);
+
+#[derive(CustomDebug)]
+pub enum Event {
+    #[debug(non_exhaustive)]
+    Key { code: u32 },
+    Click(i32, i32),
+    #[debug(non_exhaustive)]
+    Closed,
+}
+
+#[derive(CustomDebug)]
+#[debug(non_exhaustive)]
+pub enum Shape {
+    Circle(f32),
+    Empty,
+}
+
+fn main() {
+    assert_eq!(format!("{:?}", Handle { id: 1 }), "Handle { id: 1, .. }");
+    assert_eq!(
+        format!("{:?}", Cache { len: 2, entries: vec![1, 2] }),
+        "Cache { len: 2, .. }",
+    );
+    assert_eq!(format!("{:?}", Pair(1, 2)), "Pair(1, ..)");
+
+    assert_eq!(format!("{:?}", Event::Key { code: 13 }), "Key { code: 13, .. }");
+    assert_eq!(format!("{:?}", Event::Click(1, 2)), "Click(1, 2)");
+    assert_eq!(format!("{:?}", Event::Closed), "Closed { .. }");
+
+    assert_eq!(format!("{:?}", Shape::Circle(1.5)), "Circle(1.5, ..)");
+    assert_eq!(format!("{:?}", Shape::Empty), "Empty { .. }");
+
+    let expected = "\
+Cache {
+    len: 2,
+    ..
+}";
+    assert_eq!(
+        format!("{:#?}", Cache { len: 2, entries: vec![] }),
+        expected,
+    );
+}
@@ -0,0 +1,47 @@
+// Format strings given in #[debug = "..."] and #[debug(alternate = "...")]
+// are checked by the derive itself, so a mistake is reported on the string
+// literal in the attribute instead of somewhere in the expanded code.
+//
+// Besides being well-formed, the format string has to use exactly one
+// positional argument, the field value: no other positions, no named
+// arguments and no widths or precisions taken from arguments.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct UnknownTrait {
+    #[debug = "{:q}"]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct TwoArguments {
+    #[debug = "{} and {}"]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct NamedArgument {
+    #[debug = "{value:x}"]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct NoArgument {
+    #[debug = "hidden"]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Unterminated {
+    #[debug(alternate = "{:#x")]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct WidthArgument {
+    #[debug = "{:>1$}"]
+    value: u8,
+}
+
+fn main() {}
@@ -0,0 +1,165 @@
+// Logging a struct holding a huge collection, or a deeply nested one, should
+// not flood the logs.
+//
+// #[debug(max_items = N)] on a field prints only the first N items of the
+// collection, followed by `... (M more)` when some were left out. The field
+// can be of any type whose reference iterates over Debug items.
+//
+// #[debug(max_depth = N)] on the struct elides everything nested more than N
+// levels deep, where the struct's own fields are at depth 1, printing `..`
+// inside the brackets of the first elided level. Brackets and quotes inside
+// string and char literals don't count, and the limit carries over into
+// #[debug(flatten)] fields. Fields printed through a custom format are shown
+// as they are, while a field with both limits counts the brackets of its
+// list as one level and keeps the `... (M more)` marker.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeSet;
+
+#[derive(CustomDebug)]
+pub struct Batch<T> {
+    id: u32,
+    #[debug(max_items = 3)]
+    items: Vec<T>,
+    #[debug(max_items = 2)]
+    tags: BTreeSet<&'static str>,
+    #[debug(max_items = 3)]
+    short: [u8; 2],
+}
+
+#[derive(Debug)]
+pub struct Inner {
+    values: Vec<Vec<u8>>,
+    label: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 2)]
+pub struct Outer {
+    name: &'static str,
+    inner: Inner,
+    pair: (u8, Option<u8>),
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 1)]
+pub struct Shallow(Inner, &'static str);
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 1)]
+pub struct Chars {
+    open: char,
+    quote: char,
+    text: &'static str,
+    nested: Option<(char, &'static str)>,
+    #[debug = "{:?}"]
+    custom: Option<u8>,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 2)]
+pub struct Limited {
+    #[debug(max_items = 1)]
+    bytes: Vec<u8>,
+    #[debug(max_items = 1)]
+    rows: Vec<Vec<u8>>,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 1)]
+pub struct Flat {
+    #[debug(max_items = 1)]
+    rows: Vec<Vec<u8>>,
+}
+
+#[derive(CustomDebug)]
+pub struct Position {
+    line: Option<u32>,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 1)]
+pub struct Located {
+    name: &'static str,
+    #[debug(flatten)]
+    position: Position,
+}
+
+fn main() {
+    let batch = Batch {
+        id: 1,
+        items: (0..100_000).collect::<Vec<u32>>(),
+        tags: ["a", "b", "c"].into_iter().collect(),
+        short: [1, 2],
+    };
+    assert_eq!(
+        format!("{:?}", batch),
+        r#"Batch { id: 1, items: [0, 1, 2, ... (99997 more)], tags: ["a", "b", ... (1 more)], short: [1, 2] }"#,
+    );
+
+    let outer = Outer {
+        name: "a{b",
+        inner: Inner {
+            values: vec![vec![1], vec![2, 3]],
+            label: "x)",
+        },
+        pair: (1, Some(2)),
+    };
+    assert_eq!(
+        format!("{:?}", outer),
+        r#"Outer { name: "a{b", inner: Inner { values: [..], label: "x)" }, pair: (1, Some(..)) }"#,
+    );
+
+    let expected = r#"Outer {
+    name: "a{b",
+    inner: Inner {
+        values: [..],
+        label: "x)",
+    },
+    pair: (
+        1,
+        Some(..),
+    ),
+}"#;
+    assert_eq!(format!("{:#?}", outer), expected);
+
+    let shallow = Shallow(
+        Inner {
+            values: vec![],
+            label: "y",
+        },
+        "z",
+    );
+    assert_eq!(format!("{:?}", shallow), r#"Shallow(Inner { .. }, "z")"#);
+
+    let chars = Chars {
+        open: '(',
+        quote: '"',
+        text: "'[\"",
+        nested: Some((']', "}")),
+        custom: Some(1),
+    };
+    assert_eq!(
+        format!("{:?}", chars),
+        r#"Chars { open: '(', quote: '"', text: "'[\"", nested: Some(..), custom: Some(1) }"#,
+    );
+
+    let located = Located {
+        name: "x",
+        position: Position { line: Some(3) },
+    };
+    assert_eq!(format!("{:?}", located), r#"Located { name: "x", line: Some(..) }"#);
+
+    let limited = Limited {
+        bytes: vec![1, 2, 3],
+        rows: vec![vec![1], vec![2]],
+    };
+    assert_eq!(
+        format!("{:?}", limited),
+        "Limited { bytes: [1, ... (2 more)], rows: [[..], ... (1 more)] }",
+    );
+    let flat = Flat {
+        rows: vec![vec![1], vec![2]],
+    };
+    assert_eq!(format!("{:?}", flat), "Flat { rows: [..] }");
+}
@@ -0,0 +1,92 @@
+// With the `structured` cargo feature enabled, CustomDebug also generates an
+// inherent method for structured loggers:
+//
+//     pub fn debug_fields(&self, visit: impl FnMut(&'static str, &dyn Debug))
+//
+// It calls `visit` once per field, with the same names and values as the
+// Debug output: skipped fields are left out, redacted ones show their mask,
+// renamed ones use the new name, and fields of tuple structs and variants are
+// named by their index. For enums, the fields of the current variant are
+// visited, and #[debug(flatten)] fields visit the fields of their struct.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Login {
+    #[debug(rename = "user-id")]
+    user: u64,
+    #[debug(redact)]
+    password: &'static str,
+    #[debug(skip)]
+    attempt: u8,
+    #[debug = "{:x}"]
+    flags: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Session {
+    id: u32,
+    #[debug(flatten)]
+    login: Login,
+}
+
+#[derive(CustomDebug)]
+#[repr(packed)]
+pub struct Packet {
+    kind: u8,
+    #[debug(skip)]
+    payload: Vec<u8>,
+    length: u16,
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Click(i32, #[debug(skip)] i32, i32),
+    Key { code: u32 },
+    Closed,
+}
+
+fn fields(visit: impl FnOnce(&mut dyn FnMut(&'static str, &dyn std::fmt::Debug))) -> Vec<String> {
+    let mut fields = Vec::new();
+    visit(&mut |name, value| fields.push(format!("{}={:?}", name, value)));
+    fields
+}
+
+fn main() {
+    let login = Login {
+        user: 7,
+        password: "hunter2",
+        attempt: 1,
+        flags: 255,
+    };
+    assert_eq!(
+        fields(|visit| login.debug_fields(visit)),
+        ["user-id=7", "password=<redacted>", "flags=ff"],
+    );
+
+    let session = Session { id: 3, login };
+    assert_eq!(
+        fields(|visit| session.debug_fields(visit)),
+        ["id=3", "user-id=7", "password=<redacted>", "flags=ff"],
+    );
+
+    let packet = Packet {
+        kind: 2,
+        payload: vec![1, 2],
+        length: 2,
+    };
+    assert_eq!(
+        fields(|visit| packet.debug_fields(visit)),
+        ["kind=2", "length=2"],
+    );
+
+    assert_eq!(
+        fields(|visit| Event::Click(1, 2, 3).debug_fields(visit)),
+        ["0=1", "2=3"],
+    );
+    assert_eq!(
+        fields(|visit| Event::Key { code: 13 }.debug_fields(visit)),
+        ["code=13"],
+    );
+    assert!(fields(|visit| Event::Closed.debug_fields(visit)).is_empty());
+}
@@ -0,0 +1,83 @@
+// Fields of a #[repr(packed)] struct may not be aligned, so the generated
+// impl can't take references to them the way it does for other structs.
+// Instead each field is copied into a local before being formatted, which
+// requires the field types to be Copy, except for skipped fields, which
+// aren't read at all.
+//
+// Unions can't be printed field by field at all, since only the code using
+// the union knows which field is active. CustomDebug accepts them only with a
+// #[debug(with = "...")] on the union itself, naming a
+//
+//     fn(&Self, &mut fmt::Formatter) -> fmt::Result
+//
+// that prints the whole value. The same attribute also works on structs and
+// enums.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+
+#[derive(CustomDebug, Clone, Copy)]
+#[repr(C, packed)]
+pub struct Header {
+    tag: u8,
+    #[debug = "{:#06x}"]
+    length: u16,
+    #[debug(redact)]
+    checksum: u32,
+}
+
+#[derive(CustomDebug)]
+#[repr(packed(2))]
+pub struct Pair(u8, u64);
+
+#[derive(CustomDebug)]
+#[repr(packed)]
+pub struct Tagged {
+    tag: u8,
+    #[debug(skip)]
+    name: String,
+}
+
+#[derive(CustomDebug)]
+#[debug(with = "fmt_bits")]
+pub union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn fmt_bits(bits: &Bits, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Bits({:#010x})", unsafe { bits.int })
+}
+
+#[derive(CustomDebug)]
+#[debug(with = "Celsius::fmt_degrees")]
+pub struct Celsius {
+    degrees: f32,
+}
+
+impl Celsius {
+    fn fmt_degrees(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}°C", self.degrees)
+    }
+}
+
+fn main() {
+    let header = Header {
+        tag: 1,
+        length: 512,
+        checksum: 0xdeadbeef,
+    };
+    assert_eq!(
+        format!("{:?}", header),
+        "Header { tag: 1, length: 0x0200, checksum: <redacted> }",
+    );
+    assert_eq!(format!("{:?}", Pair(1, 2)), "Pair(1, 2)");
+    let tagged = Tagged {
+        tag: 3,
+        name: "x".to_owned(),
+    };
+    assert_eq!(format!("{:?}", tagged), "Tagged { tag: 3, .. }");
+
+    assert_eq!(format!("{:?}", Bits { float: 1.0 }), "Bits(0x3f800000)");
+    assert_eq!(format!("{:?}", Celsius { degrees: 21.5 }), "21.5°C");
+}
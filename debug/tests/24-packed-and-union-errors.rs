@@ -0,0 +1,19 @@
+// Fields of a #[repr(packed)] struct that aren't Copy are reported on the
+// field, and a union without #[debug(with = "...")] is reported on its name.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[repr(packed)]
+pub struct Named {
+    id: u8,
+    name: String,
+}
+
+#[derive(CustomDebug)]
+pub union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
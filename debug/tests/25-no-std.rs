@@ -0,0 +1,96 @@
+// Firmware crates are #![no_std], so everything CustomDebug generates has to
+// come from `core`: fully qualified `::core::fmt` paths and no allocation,
+// even for fields with a custom format string.
+//
+// This test crate is no_std. The standard library is only linked, for the
+// runtime that calls main, under a different name so that a generated
+// `::std` path would fail to resolve.
+
+#![no_std]
+
+extern crate std as _runtime;
+
+use core::fmt::{self, Write};
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Register {
+    name: &'static str,
+    #[debug = "{:#010x}"]
+    value: u32,
+    #[debug(alternate = "{:#b}")]
+    flags: u8,
+    #[debug(max_items = 2)]
+    history: [u16; 3],
+    #[debug(with = "level")]
+    level: u8,
+    #[debug(redact)]
+    key: u64,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 1)]
+pub struct Snapshot {
+    id: u8,
+    pair: (u8, (u8, u8)),
+}
+
+#[derive(CustomDebug)]
+pub enum Mode {
+    Off,
+    On(#[debug = "{}%"] u8),
+}
+
+fn level(level: &u8, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(if *level > 0 { "high" } else { "low" })
+}
+
+struct Buffer {
+    bytes: [u8; 256],
+    len: usize,
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl Buffer {
+    fn format(args: fmt::Arguments) -> Buffer {
+        let mut buffer = Buffer { bytes: [0; 256], len: 0 };
+        buffer.write_fmt(args).unwrap();
+        buffer
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+fn main() {
+    let register = Register {
+        name: "CTRL",
+        value: 0xbeef,
+        flags: 5,
+        history: [1, 2, 3],
+        level: 1,
+        key: 42,
+    };
+    assert_eq!(
+        Buffer::format(format_args!("{:?}", register)).as_str(),
+        r#"Register { name: "CTRL", value: 0x0000beef, flags: 5, history: [1, 2, ... (1 more)], level: high, key: <redacted> }"#,
+    );
+
+    let snapshot = Snapshot { id: 7, pair: (1, (2, 3)) };
+    assert_eq!(
+        Buffer::format(format_args!("{:?}", snapshot)).as_str(),
+        "Snapshot { id: 7, pair: (..) }",
+    );
+
+    assert_eq!(Buffer::format(format_args!("{:?}", Mode::Off)).as_str(), "Off");
+    assert_eq!(Buffer::format(format_args!("{:?}", Mode::On(40))).as_str(), "On(40%)");
+}
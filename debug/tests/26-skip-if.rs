@@ -0,0 +1,72 @@
+// Like serde's skip_serializing_if, #[debug(skip_if = "path::to::fn")] leaves
+// a field out of the output whenever the given `fn(&T) -> bool` returns true
+// for its value, which is checked each time the value is formatted. This
+// keeps unset optionals and empty collections from cluttering the output.
+//
+// Unlike #[debug(skip)], a field left out this way doesn't end the output
+// with `..`; it is noise that was removed rather than hidden information.
+// Add #[debug(non_exhaustive)] to get the `..` anyway.
+
+use derive_debug::CustomDebug;
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+#[derive(CustomDebug)]
+pub struct Request {
+    method: &'static str,
+    #[debug(skip_if = "Option::is_none")]
+    body: Option<String>,
+    #[debug(skip_if = "Vec::is_empty")]
+    headers: Vec<(&'static str, &'static str)>,
+    #[debug(skip_if = "is_zero")]
+    #[debug = "{}ms"]
+    timeout: u32,
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Click(#[debug(skip_if = "Option::is_none")] Option<(i32, i32)>, u8),
+    #[debug(non_exhaustive)]
+    Key {
+        #[debug(skip_if = "str::is_empty")]
+        text: &'static str,
+        code: u32,
+    },
+}
+
+fn main() {
+    let request = Request {
+        method: "GET",
+        body: None,
+        headers: Vec::new(),
+        timeout: 0,
+    };
+    assert_eq!(format!("{:?}", request), r#"Request { method: "GET" }"#);
+
+    let request = Request {
+        method: "POST",
+        body: Some("{}".to_owned()),
+        headers: vec![("Accept", "*/*")],
+        timeout: 30,
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        r#"Request { method: "POST", body: Some("{}"), headers: [("Accept", "*/*")], timeout: 30ms }"#,
+    );
+
+    assert_eq!(format!("{:?}", Event::Click(None, 1)), "Click(1)");
+    assert_eq!(
+        format!("{:?}", Event::Click(Some((3, 4)), 1)),
+        "Click(Some((3, 4)), 1)",
+    );
+    assert_eq!(
+        format!("{:?}", Event::Key { text: "", code: 13 }),
+        "Key { code: 13, .. }",
+    );
+    assert_eq!(
+        format!("{:#?}", Event::Key { text: "a", code: 65 }),
+        "Key {\n    text: \"a\",\n    code: 65,\n    ..\n}",
+    );
+}
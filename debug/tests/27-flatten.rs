@@ -0,0 +1,173 @@
+// #[debug(flatten)] on a field whose type is a struct deriving CustomDebug
+// prints that struct's fields inline, as if they belonged to the outer
+// struct, instead of as a nested block. This keeps shared pieces such as
+// common metadata from adding a level of nesting everywhere they're used.
+//
+// The inner struct's own attributes still apply to its fields. When it leaves
+// some of them out, the outer output ends with `..`.
+//
+// Flattening goes through a hidden trait that CustomDebug implements for
+// every struct with named fields, including #[repr(packed)] ones, so the
+// inner type has to be such a struct. A flattened field whose type is, or
+// uses, a type parameter is bounded by that trait rather than by Debug.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Meta {
+    id: u32,
+    #[debug(skip_if = "Option::is_none")]
+    owner: Option<&'static str>,
+}
+
+fn is_unowned(meta: &Meta) -> bool {
+    meta.owner.is_none()
+}
+
+#[derive(CustomDebug)]
+pub struct Auth {
+    user: &'static str,
+    #[debug(skip)]
+    password: &'static str,
+}
+
+#[derive(CustomDebug, Clone, Copy)]
+#[repr(packed)]
+pub struct Span {
+    start: u32,
+    #[debug = "+{}"]
+    len: u16,
+}
+
+#[derive(CustomDebug)]
+pub struct Token {
+    text: &'static str,
+    #[debug(flatten)]
+    span: Span,
+}
+
+#[derive(CustomDebug)]
+pub struct Document {
+    title: &'static str,
+    #[debug(flatten)]
+    meta: Meta,
+    pages: u16,
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T> {
+    #[debug(flatten)]
+    document: Document,
+    extra: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Tagged<T> {
+    tag: u8,
+    #[debug(flatten)]
+    inner: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Labeled<T> {
+    label: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Node<T> {
+    #[debug(flatten)]
+    labeled: Labeled<T>,
+    children: usize,
+}
+
+#[derive(CustomDebug)]
+pub enum Message {
+    Login {
+        #[debug(flatten)]
+        auth: Auth,
+        #[debug(flatten, skip_if = "is_unowned")]
+        meta: Meta,
+    },
+    Logout,
+}
+
+fn main() {
+    let document = Document {
+        title: "notes",
+        meta: Meta {
+            id: 7,
+            owner: Some("ann"),
+        },
+        pages: 3,
+    };
+    assert_eq!(
+        format!("{:?}", document),
+        r#"Document { title: "notes", id: 7, owner: Some("ann"), pages: 3 }"#,
+    );
+
+    let wrapper = Wrapper {
+        document: Document {
+            title: "draft",
+            meta: Meta { id: 8, owner: None },
+            pages: 1,
+        },
+        extra: [1, 2],
+    };
+    assert_eq!(
+        format!("{:?}", wrapper),
+        r#"Wrapper { title: "draft", id: 8, pages: 1, extra: [1, 2] }"#,
+    );
+    assert_eq!(
+        format!("{:#?}", wrapper),
+        "Wrapper {\n    title: \"draft\",\n    id: 8,\n    pages: 1,\n    extra: [\n        1,\n        2,\n    ],\n}",
+    );
+
+    let login = Message::Login {
+        auth: Auth {
+            user: "root",
+            password: "hunter2",
+        },
+        meta: Meta { id: 1, owner: None },
+    };
+    assert_eq!(format!("{:?}", login), r#"Login { user: "root", .. }"#);
+
+    let login = Message::Login {
+        auth: Auth {
+            user: "ann",
+            password: "hunter2",
+        },
+        meta: Meta {
+            id: 2,
+            owner: Some("ann"),
+        },
+    };
+    assert_eq!(
+        format!("{:?}", login),
+        r#"Login { user: "ann", id: 2, owner: Some("ann"), .. }"#,
+    );
+    assert_eq!(format!("{:?}", Message::Logout), "Logout");
+
+    let token = Token {
+        text: "fn",
+        span: Span { start: 4, len: 2 },
+    };
+    assert_eq!(
+        format!("{:?}", token),
+        r#"Token { text: "fn", start: 4, len: +2 }"#,
+    );
+
+    let tagged = Tagged {
+        tag: 1,
+        inner: Auth {
+            user: "root",
+            password: "hunter2",
+        },
+    };
+    assert_eq!(format!("{:?}", tagged), r#"Tagged { tag: 1, user: "root", .. }"#);
+
+    let node = Node {
+        labeled: Labeled { label: 'a' },
+        children: 2,
+    };
+    assert_eq!(format!("{:?}", node), "Node { label: 'a', children: 2 }");
+}
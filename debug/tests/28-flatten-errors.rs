@@ -0,0 +1,33 @@
+// #[debug(flatten)] replaces the field with the fields of its type, so it only
+// applies to named fields and leaves nothing about the field itself to
+// customize. The type has to be a struct with named fields deriving
+// CustomDebug; anything else is reported at the field type.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Inner {
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Renamed {
+    #[debug(flatten, rename = "inner")]
+    inner: Inner,
+}
+
+#[derive(CustomDebug)]
+pub struct Tuple(#[debug(flatten)] Inner);
+
+#[derive(Debug)]
+pub struct Plain {
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Outer {
+    #[debug(flatten)]
+    plain: Plain,
+}
+
+fn main() {}
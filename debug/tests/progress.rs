@@ -1,12 +1,34 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-impl-debug.rs");
-    //t.pass("tests/03-custom-format.rs");
-    //t.pass("tests/04-type-parameter.rs");
-    //t.pass("tests/05-phantom-data.rs");
-    //t.pass("tests/06-bound-trouble.rs");
-    //t.pass("tests/07-associated-type.rs");
-    //t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-impl-debug.rs");
+    t.pass("tests/03-custom-format.rs");
+    t.pass("tests/04-type-parameter.rs");
+    t.pass("tests/05-phantom-data.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-bound-inference.rs");
+    t.pass("tests/10-field-escape-hatch.rs");
+    t.pass("tests/11-enums.rs");
+    t.pass("tests/12-tuple-and-unit-structs.rs");
+    t.pass("tests/13-skip-and-redact.rs");
+    t.pass("tests/14-with-function.rs");
+    t.pass("tests/15-alternate-format.rs");
+    t.pass("tests/16-custom-display.rs");
+    t.compile_fail("tests/17-display-unknown-field.rs");
+    t.pass("tests/18-rename-and-transparent.rs");
+    t.pass("tests/19-non-exhaustive.rs");
+    t.compile_fail("tests/20-invalid-format.rs");
+    t.pass("tests/21-max-items-and-depth.rs");
+    t.pass("tests/23-packed-and-union.rs");
+    t.compile_fail("tests/24-packed-and-union-errors.rs");
+    t.pass("tests/25-no-std.rs");
+    t.pass("tests/26-skip-if.rs");
+    t.pass("tests/27-flatten.rs");
+    t.compile_fail("tests/28-flatten-errors.rs");
+    if cfg!(feature = "structured") {
+        t.pass("tests/22-structured.rs");
+    }
 }
@@ -1,8 +1,363 @@
 use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, Group, Ident, Literal, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens};
+use std::cmp::Ordering;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, parenthesized, parse_macro_input, token, Attribute, LitInt, Token};
+
+/// The input of `seq!(N in 0..16 { ... })`.
+struct Seq {
+    /// The variable replaced in the body, `N`.
+    var: Ident,
+    /// The values taken by the variable, in order.
+    values: Vec<i128>,
+    /// The suffix of the bounds, such as `u8`, given to every literal.
+    suffix: String,
+    /// `#[seq(hex)]`: paste values into identifiers as upper case hexadecimal
+    /// digits, all padded to this many, a whole number of bytes.
+    hex_width: Option<usize>,
+    /// Everything between the braces.
+    body: TokenStream2,
+}
+
+/// One end of the range: an integer literal in any radix, possibly negative
+/// and possibly suffixed.
+struct Bound {
+    value: i128,
+    suffix: String,
+    /// The tokens of the bound, for errors.
+    tokens: TokenStream2,
+}
+
+impl Parse for Bound {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // -8 or 0x10 or 16u8
+        let minus: Option<Token![-]> = input.parse()?;
+        let literal: LitInt = input.parse()?;
+        let value = literal.base10_parse::<i128>()?;
+        Ok(Bound {
+            value: if minus.is_some() { -value } else { value },
+            suffix: literal.suffix().to_owned(),
+            tokens: quote!(#minus #literal),
+        })
+    }
+}
+
+/// The most values a range may have, as the body is copied once for each.
+const MAX_VALUES: u128 = 1 << 16;
+
+/// The values of `0..16`, `0..=16`, or of such a range in parentheses followed
+/// by `.step_by(n)` and `.rev()` calls, applied in order. They are kept as an
+/// arithmetic sequence, so that stepping through a huge range doesn't list
+/// all of its values first.
+struct Range {
+    first: i128,
+    /// The difference between consecutive values.
+    step: i128,
+    len: u128,
+    suffix: String,
+    /// The tokens of the range, for errors.
+    tokens: TokenStream2,
+}
+
+impl Range {
+    fn values(&self) -> impl Iterator<Item = i128> + '_ {
+        // every value fits in an i128, so wrapping only cancels out
+        // intermediate overflows
+        (0..self.len).map(|i| self.first.wrapping_add(self.step.wrapping_mul(i as i128)))
+    }
+}
+
+impl Parse for Range {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // (0..64).step_by(8).rev()
+        // ^^^^^^^
+        if input.peek(token::Paren) {
+            let content;
+            let paren = parenthesized!(content in input);
+            let mut range: Range = content.parse()?;
+            let mut tokens = TokenStream2::new();
+            paren.surround(&mut tokens, |tokens| tokens.extend(range.tokens.clone()));
+            while input.peek(Token![.]) {
+                input.parse::<Token![.]>()?.to_tokens(&mut tokens);
+                let method: Ident = input.parse()?;
+                method.to_tokens(&mut tokens);
+                let args;
+                let paren = parenthesized!(args in input);
+                // the group's span covers the arguments
+                paren.surround(&mut tokens, |_| {});
+                if method == "step_by" {
+                    // (0..64).step_by(8)
+                    //                 ^
+                    let step: Bound = args.parse()?;
+                    if step.value <= 0 {
+                        return Err(syn::Error::new_spanned(
+                            step.tokens,
+                            "the step of `step_by` has to be a positive integer",
+                        ));
+                    }
+                    range.len = range.len.div_ceil(step.value as u128);
+                    if range.len > 1 {
+                        range.step = range.step.checked_mul(step.value).ok_or_else(|| {
+                            syn::Error::new_spanned(&step.tokens, "step is too large")
+                        })?;
+                    }
+                } else if method == "rev" {
+                    // (0..16).rev()
+                    if range.len > 0 {
+                        let last = range.step.wrapping_mul(range.len as i128 - 1);
+                        range.first = range.first.wrapping_add(last);
+                        range.step = -range.step;
+                    }
+                } else {
+                    return Err(syn::Error::new(
+                        method.span(),
+                        "expected `step_by(n)` or `rev()`",
+                    ));
+                }
+                if !args.is_empty() {
+                    return Err(args.error("unexpected argument"));
+                }
+            }
+            range.tokens = tokens;
+            return Ok(range);
+        }
+
+        // 0..16 or 0..=16
+        let start: Bound = input.parse()?;
+        let inclusive = input.peek(Token![..=]);
+        let dots = if inclusive {
+            input.parse::<Token![..=]>()?.into_token_stream()
+        } else {
+            input.parse::<Token![..]>()?.into_token_stream()
+        };
+        let end: Bound = input.parse()?;
+        let suffix = match (start.suffix.as_str(), end.suffix.as_str()) {
+            (suffix, "") | ("", suffix) => suffix.to_owned(),
+            (start, end) if start == end => start.to_owned(),
+            (start, _) => {
+                return Err(syn::Error::new_spanned(
+                    end.tokens,
+                    format!(
+                        "expected the same suffix as the start of the range, `{}`",
+                        start
+                    ),
+                ));
+            }
+        };
+        let len = match (inclusive, start.value.cmp(&end.value)) {
+            (_, Ordering::Less) => start.value.abs_diff(end.value) + inclusive as u128,
+            (true, Ordering::Equal) => 1,
+            _ => 0,
+        };
+        let (start_tokens, end_tokens) = (&start.tokens, &end.tokens);
+        Ok(Range {
+            first: start.value,
+            step: 1,
+            len,
+            suffix,
+            tokens: quote!(#start_tokens #dots #end_tokens),
+        })
+    }
+}
+
+impl Parse for Seq {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // #[seq(hex)] N in 0..16 { ... }
+        // ^^^^^^^^^^^
+        let mut hex = false;
+        for attr in input.call(Attribute::parse_outer)? {
+            if !attr.path().is_ident("seq") {
+                return Err(syn::Error::new_spanned(attr, "expected `#[seq(...)]`"));
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("hex") {
+                    hex = true;
+                    return Ok(());
+                }
+                Err(meta.error("unrecognized seq option, expected `hex`"))
+            })?;
+        }
+
+        // N in 0..16 { ... }
+        // ^
+        let var: Ident = input.parse()?;
+        input.parse::<Token![in]>()?;
+
+        // N in 0..16 { ... } or N in (0..64).step_by(8) { ... }
+        //      ^^^^^            ^^^^^^^^^^^^^^^^^^^^^^
+        let range: Range = input.parse()?;
+        if range.len > MAX_VALUES {
+            return Err(syn::Error::new_spanned(
+                &range.tokens,
+                format!(
+                    "this range has {} values, more than the {} that seq! repeats its body for",
+                    range.len, MAX_VALUES
+                ),
+            ));
+        }
+        let values: Vec<i128> = range.values().collect();
+        let suffix = range.suffix;
+
+        // negative values can't be pasted, so only the largest non-negative
+        // one decides the width
+        let hex_width = hex.then(|| {
+            values
+                .iter()
+                .filter(|&&value| value >= 0)
+                .max()
+                .map_or(1, |max| format!("{:X}", max).len())
+                .next_multiple_of(2)
+        });
+
+        // N in 0..16 { ... }
+        //            ^^^^^^^
+        let content;
+        braced!(content in input);
+        let body = content.parse()?;
+
+        Ok(Seq {
+            var,
+            values,
+            suffix,
+            hex_width,
+            body,
+        })
+    }
+}
 
 #[proc_macro]
 pub fn seq(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let seq = parse_macro_input!(input as Seq);
+    seq.expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+impl Seq {
+    fn expand(&self) -> syn::Result<TokenStream2> {
+        // with #(...)* sections only those are repeated, otherwise the whole body
+        match self.repeat_sections(&self.body)? {
+            Some(expanded) => Ok(expanded),
+            None => self
+                .values
+                .iter()
+                .map(|&value| self.substitute(&self.body, value))
+                .collect(),
+        }
+    }
+
+    /// A copy of `body` in which every `#(...)*` section is replaced by its
+    /// contents substituted once per value, or `None` if there are no such
+    /// sections.
+    fn repeat_sections(&self, body: &TokenStream2) -> syn::Result<Option<TokenStream2>> {
+        let tokens: Vec<TokenTree> = body.clone().into_iter().collect();
+        let mut found = false;
+        let mut expanded = TokenStream2::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            // #( ... )*
+            if let [TokenTree::Punct(pound), TokenTree::Group(section), TokenTree::Punct(star), ..] =
+                &tokens[i..]
+            {
+                if pound.as_char() == '#'
+                    && section.delimiter() == Delimiter::Parenthesis
+                    && star.as_char() == '*'
+                {
+                    for &value in &self.values {
+                        expanded.extend(self.substitute(&section.stream(), value)?);
+                    }
+                    found = true;
+                    i += 3;
+                    continue;
+                }
+            }
+            match &tokens[i] {
+                TokenTree::Group(group) => match self.repeat_sections(&group.stream())? {
+                    Some(stream) => {
+                        let mut repeated = Group::new(group.delimiter(), stream);
+                        repeated.set_span(group.span());
+                        expanded.extend([TokenTree::Group(repeated)]);
+                        found = true;
+                    }
+                    None => expanded.extend([tokens[i].clone()]),
+                },
+                tt => expanded.extend([tt.clone()]),
+            }
+            i += 1;
+        }
+        Ok(found.then_some(expanded))
+    }
+
+    /// A copy of `body` with every occurrence of the variable replaced by
+    /// `value` as an integer literal with the suffix of the bounds, spanned
+    /// where the variable was so that errors still point into the macro input.
+    /// `Prefix~N` is pasted into the single identifier `Prefix{value}`, spanned
+    /// at `Prefix`.
+    fn substitute(&self, body: &TokenStream2, value: i128) -> syn::Result<TokenStream2> {
+        let tokens: Vec<TokenTree> = body.clone().into_iter().collect();
+        let mut substituted = TokenStream2::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            // Irq~N
+            if let [TokenTree::Ident(prefix), TokenTree::Punct(tilde), TokenTree::Ident(var), ..] =
+                &tokens[i..]
+            {
+                if tilde.as_char() == '~' && *var == self.var {
+                    let pasted = self.paste(prefix, value)?;
+                    substituted.extend([TokenTree::Ident(pasted)]);
+                    i += 3;
+                    continue;
+                }
+            }
+            let tt = match &tokens[i] {
+                TokenTree::Ident(ident) if *ident == self.var => {
+                    let mut literal: Literal = format!("{}{}", value, self.suffix)
+                        .parse()
+                        .expect("an integer is a valid literal");
+                    literal.set_span(ident.span());
+                    if value < 0 {
+                        // a negative literal is really `-` applied to one, so
+                        // it's parenthesized to keep `N.pow(2)` meaning `(-2).pow(2)`
+                        let mut group =
+                            Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+                        group.set_span(ident.span());
+                        TokenTree::Group(group)
+                    } else {
+                        TokenTree::Literal(literal)
+                    }
+                }
+                TokenTree::Group(group) => {
+                    let stream = self.substitute(&group.stream(), value)?;
+                    let mut copy = Group::new(group.delimiter(), stream);
+                    copy.set_span(group.span());
+                    TokenTree::Group(copy)
+                }
+                tt => tt.clone(),
+            };
+            substituted.extend([tt]);
+            i += 1;
+        }
+        Ok(substituted)
+    }
 
-    unimplemented!()
+    /// `prefix` followed by the digits of `value`, which are decimal or, with
+    /// `#[seq(hex)]`, hexadecimal zero-padded to whole bytes, so that `Reg~N`
+    /// becomes `Reg0A`.
+    fn paste(&self, prefix: &Ident, value: i128) -> syn::Result<Ident> {
+        if value < 0 {
+            return Err(syn::Error::new(
+                prefix.span(),
+                format!(
+                    "cannot paste the negative value {} into an identifier",
+                    value
+                ),
+            ));
+        }
+        let digits = match self.hex_width {
+            Some(width) => format!("{:0width$X}", value, width = width),
+            None => value.to_string(),
+        };
+        Ok(Ident::new(&format!("{}{}", prefix, digits), prefix.span()))
+    }
 }
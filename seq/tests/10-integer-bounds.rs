@@ -0,0 +1,71 @@
+// The bounds of the range can be any integer literals: negative, written in
+// hexadecimal, octal or binary, or suffixed. A suffix on either bound is
+// carried over to every literal N expands to, so N becomes `3u8` rather than
+// `3`, which fixes the type of the generated values. A negative N stays a
+// single operand, so `N.pow(2)` squares it rather than negating the square.
+//
+// Pasted identifiers use decimal digits by default. With #[seq(hex)] they use
+// upper case hexadecimal digits instead, zero-padded to a whole number of
+// bytes for the largest value, so Reg~N becomes Reg0A.
+
+use seq::seq;
+
+seq!(N in -2..=2 {
+    const OFFSETS: [i32; 5] = [#(N * 10,)*];
+});
+
+seq!(N in -2i32..=2i32 {
+    const SQUARES: [i32; 5] = [#(N.pow(2),)*];
+    const NEGATED: [i32; 5] = [#(-N,)*];
+});
+
+seq!(N in -1..0 {
+    const MINUS_ONE: i8 = N;
+});
+
+seq!(N in 0x00..0x04 {
+    fn mask~N() -> u8 {
+        1 << N
+    }
+});
+
+seq!(N in 0b000..0b011 {
+    const BITS: [u32; 3] = [#(N,)*];
+});
+
+seq!(N in 0u8..16u8 {
+    const BYTES: [u8; 16] = [#(N,)*];
+});
+
+seq!(N in 0..=300u16 {
+    fn last() -> u16 {
+        #(let _ = N;)*
+        300
+    }
+});
+
+seq!(#[seq(hex)] N in 8..0x0C {
+    #[derive(Debug, PartialEq)]
+    enum Reg {
+        #(Reg~N = N,)*
+    }
+});
+
+seq!(#[seq(hex)] N in 0..0x4000 {
+    #(struct Page~N;)*
+});
+
+fn main() {
+    assert_eq!(OFFSETS, [-20, -10, 0, 10, 20]);
+    assert_eq!(SQUARES, [4, 1, 0, 1, 4]);
+    assert_eq!(NEGATED, [2, 1, 0, -1, -2]);
+    assert_eq!(MINUS_ONE, -1);
+    assert_eq!([mask0(), mask1(), mask2(), mask3()], [1, 2, 4, 8]);
+    assert_eq!(BITS, [0, 1, 2]);
+    assert_eq!(BYTES[15], 15);
+    assert_eq!(last(), 300);
+    assert_eq!(Reg::Reg0A as u8, 10);
+    assert_eq!(Reg::Reg0B as u8, 11);
+    assert_eq!(format!("{:?}", Reg::Reg08), "Reg08");
+    let _ = (Page0000, Page00FF, Page3FFF);
+}
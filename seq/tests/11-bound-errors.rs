@@ -0,0 +1,15 @@
+// When both bounds have a suffix, the suffixes have to agree. A negative value
+// can't be pasted into an identifier, as `-` isn't part of one, and hex is
+// the only option #[seq(...)] takes.
+
+use seq::seq;
+
+seq!(N in 0u8..16u16 {});
+
+seq!(N in -1..1 {
+    fn f~N() {}
+});
+
+seq!(#[seq(octal)] N in 0..8 {});
+
+fn main() {}
@@ -0,0 +1,28 @@
+// A range in parentheses can be followed by `.step_by(n)`, which keeps every
+// n-th value, and `.rev()`, which reverses the order, the same way as on an
+// iterator. Several calls are applied from left to right.
+
+use seq::seq;
+
+seq!(N in (0..64).step_by(8) {
+    const OFFSETS: [u32; 8] = [#(N,)*];
+});
+
+seq!(N in (0..4).rev() {
+    const COUNTDOWN: [u32; 4] = [#(N,)*];
+});
+
+seq!(N in (0..=10u8).step_by(5).rev() {
+    const STEPS: [u8; 3] = [#(N,)*];
+});
+
+seq!(N in (0x00..0x20).step_by(0x10) {
+    struct Bank~N;
+});
+
+fn main() {
+    assert_eq!(OFFSETS, [0, 8, 16, 24, 32, 40, 48, 56]);
+    assert_eq!(COUNTDOWN, [3, 2, 1, 0]);
+    assert_eq!(STEPS, [10, 5, 0]);
+    let _ = (Bank0, Bank16);
+}
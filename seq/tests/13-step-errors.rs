@@ -0,0 +1,12 @@
+// A step of zero would never get past the first value, and a negative step
+// doesn't mean anything; both are errors pointing at the step.
+
+use seq::seq;
+
+seq!(N in (0..8).step_by(0) {});
+
+seq!(N in (0..8).step_by(-2) {});
+
+seq!(N in (0..8).skip(1) {});
+
+fn main() {}
@@ -0,0 +1,24 @@
+// Stepping through a huge range only produces the values that are kept, so
+// `(0..100_000_000u64).step_by(50_000_000)` is as cheap as `0..2`. A range
+// that would still repeat the body more than 65536 times is an error pointing
+// at the range, rather than a compiler that runs out of memory.
+
+use seq::seq;
+
+seq!(N in (0..100_000_000u64).step_by(50_000_000) {
+    const HALVES: [u64; 2] = [#(N,)*];
+});
+
+seq!(N in (-170141183460469231731687303715884105727..=170141183460469231731687303715884105727).step_by(170141183460469231731687303715884105727).rev() {
+    const EXTREMES: [i128; 3] = [#(N,)*];
+});
+
+seq!(N in (0..1_000_000).rev().step_by(250_000) {
+    const QUARTERS: [u32; 4] = [#(N,)*];
+});
+
+fn main() {
+    assert_eq!(HALVES, [0, 50_000_000]);
+    assert_eq!(EXTREMES, [i128::MAX, 0, -i128::MAX]);
+    assert_eq!(QUARTERS, [999_999, 749_999, 499_999, 249_999]);
+}
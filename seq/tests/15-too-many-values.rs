@@ -0,0 +1,12 @@
+// Anything beyond 65536 copies of the body is rejected, however the range
+// gets there.
+
+use seq::seq;
+
+seq!(N in 0..100_000 {});
+
+seq!(N in (0..=0xFFFF_FFFF_FFFF_FFFFu64).step_by(2) {});
+
+seq!(N in (0..1_000_000).rev().step_by(10) {});
+
+fn main() {}